@@ -1,4 +1,6 @@
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, sysvars::rent::Rent, ProgramResult,
+};
 
 #[inline(always)]
 pub fn close_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
@@ -12,3 +14,40 @@ pub fn close_account(account: &AccountInfo, destination: &AccountInfo) -> Progra
     account.realloc(1, true)?;
     account.close()
 }
+
+/// Moves whatever `account` holds above the rent-exempt minimum for its current size
+/// to `destination`, e.g. after the account was shrunk.
+#[inline(always)]
+pub fn sweep_excess_rent(
+    account: &AccountInfo,
+    destination: &AccountInfo,
+    rent: &Rent,
+) -> ProgramResult {
+    let excess = account
+        .lamports()
+        .saturating_sub(rent.minimum_balance(account.data_len()));
+
+    if excess > 0 {
+        *account.try_borrow_mut_lamports()? -= excess;
+        *destination.try_borrow_mut_lamports()? += excess;
+    }
+
+    Ok(())
+}
+
+/// Shrinks `account` to `len` bytes if it is larger, sweeping the rent it no longer needs
+/// to `destination`.
+#[inline(always)]
+pub fn shrink_account(
+    account: &AccountInfo,
+    len: usize,
+    destination: &AccountInfo,
+    rent: &Rent,
+) -> ProgramResult {
+    if account.data_len() > len {
+        account.resize(len)?;
+        sweep_excess_rent(account, destination, rent)?;
+    }
+
+    Ok(())
+}
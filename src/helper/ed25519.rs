@@ -0,0 +1,68 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+    sysvars::instructions::Instructions,
+};
+
+/// Native program that verifies ed25519 signatures listed in its instruction data
+pub const ED25519_PROGRAM_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("Ed25519SigVerify111111111111111111111111111");
+
+// Offsets into a single-signature Ed25519 program instruction
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+/// Instruction index the Ed25519 program reads as "this instruction's own data"
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Reads the signer and message of the Ed25519 program instruction right before the current
+/// one. The runtime already failed the transaction if the signature was invalid, so this only
+/// has to make sure it verified exactly one signature over data held in its own instruction.
+pub fn load_signed_message<const N: usize>(
+    instructions_sysvar: &AccountInfo,
+) -> Result<(Pubkey, [u8; N]), ProgramError> {
+    let instructions = Instructions::try_from(instructions_sysvar)?;
+    let current = instructions.load_current_index();
+    if current == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let verify_ix = instructions.load_instruction_at(current as usize - 1)?;
+    if verify_ix.get_program_id() != &ED25519_PROGRAM_ID {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let data = verify_ix.get_instruction_data();
+    if data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN || data[0] != 1 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let offsets = &data[SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN];
+    let read = |idx: usize| u16::from_le_bytes([offsets[idx * 2], offsets[idx * 2 + 1]]);
+    let (signature_offset, signature_ix) = (read(0) as usize, read(1));
+    let (pubkey_offset, pubkey_ix) = (read(2) as usize, read(3));
+    let (message_offset, message_len, message_ix) = (read(4) as usize, read(5) as usize, read(6));
+
+    // Anything pointing into another instruction could verify a signature over bytes other
+    // than the ones read here
+    if signature_ix != CURRENT_INSTRUCTION
+        || pubkey_ix != CURRENT_INSTRUCTION
+        || message_ix != CURRENT_INSTRUCTION
+        || message_len != N
+        || data.len() < signature_offset + SIGNATURE_LEN
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let signer: Pubkey = data
+        .get(pubkey_offset..pubkey_offset + PUBKEY_LEN)
+        .and_then(|key| key.try_into().ok())
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let message: [u8; N] = data
+        .get(message_offset..message_offset + N)
+        .and_then(|message| message.try_into().ok())
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    Ok((signer, message))
+}
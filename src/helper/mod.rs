@@ -1,9 +1,11 @@
 pub mod account_checks;
 pub mod account_close;
 pub mod account_init;
+pub mod ed25519;
 pub mod utils;
 
 pub use account_checks::*;
 pub use account_close::*;
 pub use account_init::*;
+pub use ed25519::*;
 pub use utils::*;
@@ -0,0 +1,146 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Seed,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::helper::{
+    account_checks::check_signer,
+    account_close::close_account,
+    account_init::{create_pda_account, StateDefinition},
+};
+use crate::state::{InvitationState, MemberState, MultisigState};
+
+/// Opens (or reopens an expired) invitation for the member described by `data`,
+/// in the layout `add_member` expects.
+pub(crate) fn invite_member(
+    payer: &AccountInfo,
+    multisig_account: &AccountInfo,
+    multisig_state: &MultisigState,
+    member_area: &[u8],
+    invitation_acc: &AccountInfo,
+    rent_acc: &AccountInfo,
+    data: &[u8],
+) -> ProgramResult {
+    let (invitee, role, permissions) = MemberState::parse_record(data)?;
+
+    let current_members = &member_area[..multisig_state.num_members as usize * MemberState::LEN];
+    if MemberState::find(current_members, &invitee)?.is_some() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (pda_invitation, invitation_bump) =
+        InvitationState::find_pda(multisig_account.key(), &invitee);
+    if pda_invitation.ne(invitation_acc.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let clock = Clock::get()?;
+
+    if invitation_acc.data_is_empty() {
+        let rent = Rent::from_account_info(rent_acc)?;
+        let bump_bytes = [invitation_bump];
+        let signer_seeds = [
+            Seed::from(InvitationState::SEED.as_bytes()),
+            Seed::from(multisig_account.key()),
+            Seed::from(&invitee),
+            Seed::from(&bump_bytes[..]),
+        ];
+        create_pda_account::<InvitationState>(payer, invitation_acc, &signer_seeds, &rent)?;
+    } else if !InvitationState::from_account_info(invitation_acc)?.expired(&clock)? {
+        // Still waiting on the invitee
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let invitation = InvitationState::from_account_info(invitation_acc)?;
+    invitation.expiry = multisig_state
+        .current_time(&clock)?
+        .checked_add(multisig_state.invitation_ttl)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    invitation.multisig = *multisig_account.key();
+    invitation.invitee = invitee;
+    invitation.role = role;
+    invitation.permissions = permissions;
+    invitation.bump = invitation_bump;
+    invitation.time_unit = multisig_state.time_unit;
+    invitation._padding = [0; 4];
+
+    Ok(())
+}
+
+/// Signed by an invited key to join the multisig before its invitation expires
+pub fn process_accept_membership_instruction(
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let [invitee, multisig_account, invitation_acc, rent_collector, rent_acc, _system_program, _remaining @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(invitee)?;
+
+    let invitation = InvitationState::from_account_info(invitation_acc)?;
+
+    if invitation.invitee != *invitee.key() || invitation.multisig != *multisig_account.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let derived = pinocchio_pubkey::derive_address(
+        &[
+            InvitationState::SEED.as_bytes(),
+            multisig_account.key().as_slice(),
+            invitee.key().as_slice(),
+        ],
+        Some(invitation.bump),
+        &crate::ID,
+    );
+    if derived != *invitation_acc.key() {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let multisig_state = MultisigState::from_account_info(multisig_account)?;
+    if multisig_state.rent_collector != *rent_collector.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if invitation.expired(&Clock::get()?)? {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let member_data = invitation.member_data();
+
+    close_account(invitation_acc, rent_collector)?;
+
+    let new_size = multisig_account.data_len() + MemberState::LEN;
+    let rent = Rent::from_account_info(rent_acc)?;
+    let rent_diff = rent
+        .minimum_balance(new_size)
+        .saturating_sub(multisig_account.lamports());
+
+    if rent_diff > 0 {
+        Transfer {
+            from: invitee,
+            to: multisig_account,
+            lamports: rent_diff,
+        }
+        .invoke()?;
+    }
+
+    multisig_account.resize(new_size)?;
+
+    let (_, member_area) = unsafe {
+        multisig_account
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
+
+    super::add_member::add_member(multisig_state, member_area, &member_data)?;
+    multisig_state.check_invariants(member_area)?;
+
+    Ok(())
+}
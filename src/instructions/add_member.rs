@@ -1,99 +1,53 @@
-use crate::helper::account_init::StateDefinition;
 use crate::state::{
     member::{MemberRole, MemberState},
     multisig::MultisigState,
 };
-use pinocchio::{
-    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, sysvars::rent::Rent,
-    ProgramResult,
-};
-use pinocchio_system::instructions::Transfer;
-
-pub(crate) fn add_member(accounts: &[&AccountInfo], data: &[u8]) -> ProgramResult {
-    let [payer, multisig_account, rent_acc, system_program_acc, _remaining @ ..] = accounts else {
-        return Err(ProgramError::NotEnoughAccountKeys);
-    };
-
-    if data.len() < 33 {
-        return Err(ProgramError::InvalidInstructionData);
+use pinocchio::{program_error::ProgramError, ProgramResult};
+
+/// Inserts a member into `member_data`, which must have room for one more member
+pub(crate) fn add_member(
+    multisig_state: &mut MultisigState,
+    member_data: &mut [u8],
+    data: &[u8],
+) -> ProgramResult {
+    let (new_member_pubkey, role, permissions) = MemberState::parse_record(data)?;
+
+    let total_members = multisig_state.num_members as usize;
+    if member_data.len() < (total_members + 1) * MemberState::LEN {
+        return Err(ProgramError::InvalidAccountData);
     }
 
-    let rent = Rent::from_account_info(rent_acc)?;
-    let mut multisig_state = MultisigState::from_account_info(multisig_account)?;
-
-    let mut pk_bytes = [0u8; 32];
-    pk_bytes.copy_from_slice(&data[..32]);
-    let new_member_pubkey = Pubkey::from(pk_bytes);
-    let role = data[32]; // 1 = admin, 0 = member
-
-    // Get current members data
-    let (_, member_data) = unsafe {
-        multisig_account
-            .borrow_mut_data_unchecked()
-            .split_at_mut_unchecked(MultisigState::LEN)
-    };
-
     // Check for duplicate
-    for m in member_data.chunks_exact(MemberState::LEN) {
-        let existing = MemberState::from_bytes(m)?;
-        if existing.pubkey == new_member_pubkey {
-            return Err(ProgramError::InvalidInstructionData);
-        }
+    let current_members = &member_data[..total_members * MemberState::LEN];
+    if MemberState::find(current_members, &new_member_pubkey)?.is_some() {
+        return Err(ProgramError::InvalidInstructionData);
     }
 
     let new_member = MemberState {
         pubkey: new_member_pubkey,
+        permissions,
     };
 
     // Find insert position: after last admin (if adding admin), or at end
     let insert_pos = if role == MemberRole::Admin as u8 {
         multisig_state.admin_counter as usize
     } else {
-        multisig_state.num_members as usize
-    };
-
-    // Resize account to add new member
-    let new_size = multisig_account.data_len() + MemberState::LEN;
-    let rent_diff = rent.minimum_balance(new_size) - multisig_account.lamports();
-
-    if rent_diff > 0 {
-        Transfer {
-            from: payer,
-            to: multisig_account,
-            lamports: rent_diff,
-        }
-        .invoke()?;
-    }
-
-    multisig_account.resize(new_size)?;
-
-    // Get updated member data after resize
-    let (_, new_member_data) = unsafe {
-        multisig_account
-            .borrow_mut_data_unchecked()
-            .split_at_mut_unchecked(MultisigState::LEN)
+        total_members
     };
 
     if role == MemberRole::Admin as u8 {
         // Admin: insert at admin_counter position and shift all normal members right
         let shift_start = insert_pos * MemberState::LEN;
-        let shift_end = multisig_state.num_members as usize * MemberState::LEN;
+        let shift_end = total_members * MemberState::LEN;
 
         // Shift all normal members right to make space for new admin
-        for i in (shift_start..shift_end).rev() {
-            new_member_data[i + MemberState::LEN] = new_member_data[i];
-        }
-        // Insert the new admin at admin_counter position
-        let insert_start = insert_pos * MemberState::LEN;
-        let insert_end = insert_start + MemberState::LEN;
-        new_member_data[insert_start..insert_end].copy_from_slice(&new_member.to_bytes()?);
-    } else {
-        // Normal member: just append at the end
-        let insert_start = insert_pos * MemberState::LEN;
-        let insert_end = insert_start + MemberState::LEN;
-        new_member_data[insert_start..insert_end].copy_from_slice(&new_member.to_bytes()?);
+        member_data.copy_within(shift_start..shift_end, shift_start + MemberState::LEN);
     }
 
+    let insert_start = insert_pos * MemberState::LEN;
+    let insert_end = insert_start + MemberState::LEN;
+    member_data[insert_start..insert_end].copy_from_slice(&new_member.to_bytes()?);
+
     // Update counters
     multisig_state.num_members = multisig_state
         .num_members
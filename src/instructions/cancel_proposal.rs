@@ -0,0 +1,90 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::helper::{
+    account_checks::check_signer,
+    account_close::shrink_account,
+    account_init::StateDefinition,
+    utils::{load_ix_data, DataLen},
+};
+use crate::state::{
+    member::{MemberState, Permission},
+    multisig::MultisigState,
+    proposal::{ProposalState, ProposalStatus},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct CancelProposalIxData {
+    pub multisig_bump: u8,
+}
+
+impl DataLen for CancelProposalIxData {
+    const LEN: usize = core::mem::size_of::<CancelProposalIxData>();
+}
+
+/// Lets a member holding the `Cancel` permission stop a proposal that hasn't been
+/// executed yet, including an approved one still waiting out the timelock. Its vote
+/// storage is dropped, with the rent going to the multisig's rent collector.
+pub fn process_cancel_proposal_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [canceller, multisig_account, proposal_account, rent_collector, _remaining @ ..] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(canceller)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let ix_data = unsafe { load_ix_data::<CancelProposalIxData>(data)? };
+
+    let multisig_state = MultisigState::from_account_info(multisig_account)?;
+    multisig_state.validate_pda(multisig_account.key(), ix_data.multisig_bump)?;
+
+    let (_, member_data) = unsafe {
+        multisig_account
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
+
+    if multisig_state.rent_collector != *rent_collector.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    match MemberState::find(member_data, canceller.key())? {
+        Some((_, member)) if member.has_permission(Permission::Cancel) => {}
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+
+    let proposal = ProposalState::from_account_info(proposal_account)?;
+
+    ProposalState::validate_pda(
+        proposal_account.key(),
+        multisig_account.key(),
+        proposal.bump,
+        proposal.proposal_id,
+    )?;
+
+    match proposal.status {
+        ProposalStatus::Draft | ProposalStatus::Active => {}
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+
+    proposal.status = ProposalStatus::Cancelled;
+    multisig_state.close_proposal(proposal.optimistic);
+
+    shrink_account(
+        proposal_account,
+        ProposalState::LEN,
+        rent_collector,
+        &Rent::get()?,
+    )?;
+
+    Ok(())
+}
@@ -4,9 +4,10 @@ use crate::helper::{
     utils::{load_ix_data, DataLen},
 };
 use crate::state::{
-    member::MemberState,
+    member::{MemberState, Permission},
     multisig::MultisigState,
     proposal::{self, ProposalState, ProposalStatus, ProposalType},
+    transaction::TransactionState,
 };
 use pinocchio::{
     account_info::AccountInfo,
@@ -21,9 +22,11 @@ use pinocchio::{
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct CreateProposalIxData {
-    pub expiry: u64,       // 8 bytes
+    pub expiry: u64,       // 8 bytes, voting period in time units, 0 for the default
     pub primary_seed: u16, // 2 bytes
     pub tx_type: ProposalType,
+    pub optimistic: u8, // 1 for a proposal that passes after the challenge window unless vetoed
+    pub options: u8,    // Transactions bound after the system program, 0 for an unbound proposal
 }
 
 impl DataLen for CreateProposalIxData {
@@ -31,7 +34,7 @@ impl DataLen for CreateProposalIxData {
 }
 
 pub fn process_create_proposal_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [creator, proposal_account, multisig_account, rent_sysvar_acc, clock_sysvar_acc, _remaining @ ..] =
+    let [creator, proposal_account, multisig_account, rent_sysvar_acc, clock_sysvar_acc, _system_program, remaining @ ..] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -47,36 +50,58 @@ pub fn process_create_proposal_instruction(accounts: &[AccountInfo], data: &[u8]
     let ix_data = unsafe { load_ix_data::<CreateProposalIxData>(&data)? };
 
     let multisig = MultisigState::from_account_info(multisig_account)?;
+    let optimistic = ix_data.optimistic != 0;
+
+    // Only members holding `Initiate` may create proposals, and optimistic ones also need
+    // `ProposeOptimistic`
+    let (_, member_data) = unsafe {
+        multisig_account
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
 
-    // Check if creator is an admin (only if there are admins)
-    if multisig.admin_counter > 0 {
-        let admin_counter = multisig.admin_counter;
-
-        // Get all members from the multisig account
-        let (_, member_data) = unsafe {
-            multisig_account
-                .borrow_mut_data_unchecked()
-                .split_at_mut_unchecked(MultisigState::LEN)
-        };
-
-        // Check if creator is an admin
-        let mut is_creator_admin = false;
-        for i in 0..admin_counter as usize {
-            let member_start = i * MemberState::LEN;
-            let member_end = member_start + MemberState::LEN;
-            let member_bytes = &member_data[member_start..member_end];
-            let member_pubkey =
-                Pubkey::from(*unsafe { &*(member_bytes.as_ptr() as *const [u8; 32]) });
-
-            if member_pubkey == *creator.key() {
-                is_creator_admin = true;
-                break;
-            }
-        }
+    match MemberState::find(member_data, creator.key())? {
+        Some((_, member))
+            if member.has_permission(Permission::Initiate)
+                && (!optimistic || member.has_permission(Permission::ProposeOptimistic)) => {}
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
 
-        if !is_creator_admin {
+    // A proposal bound to one transaction only executes that one, with several members
+    // pick between them. Each is created beforehand.
+    let options = ix_data.options as usize;
+    if options > ProposalState::MAX_OPTIONS || options > remaining.len() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut candidates = [Pubkey::default(); ProposalState::MAX_OPTIONS];
+    let mut transfer_amounts = [ProposalState::NO_TRANSFER; ProposalState::MAX_OPTIONS];
+    for (idx, candidate) in remaining[..options].iter().enumerate() {
+        if candidate.owner() != &crate::ID || candidate.data_len() < TransactionState::LEN {
             return Err(ProgramError::InvalidAccountData);
         }
+        if candidates[..idx].contains(candidate.key()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        candidates[idx] = *candidate.key();
+
+        // Kept so votes can hold a transfer to its tier before it is executed
+        if let Some(amount) = TransactionState::from_account_info(candidate)?.transfer_amount() {
+            transfer_amounts[idx] = amount;
+        }
+    }
+
+    // Without a reject threshold nothing could stop an optimistic proposal, and members can
+    // only weigh a veto against the one transaction it is bound to: an unbound one would run
+    // whatever transaction is next once the window closes, and without yes votes nothing
+    // would pick between several. Member and config changes always go through a vote.
+    if optimistic
+        && (multisig.challenge_window == 0
+            || multisig.reject_threshold == 0
+            || options != 1
+            || ix_data.tx_type != ProposalType::Cpi)
+    {
+        return Err(ProgramError::InvalidInstructionData);
     }
 
     let seeds = &[
@@ -101,17 +126,34 @@ pub fn process_create_proposal_instruction(accounts: &[AccountInfo], data: &[u8]
 
     create_pda_account::<ProposalState>(&creator, &proposal_account, &signer_seeds, &rent_account)?;
 
-    let current_time = Clock::from_account_info(clock_sysvar_acc)?.unix_timestamp as u64;
+    let current_time = multisig.current_time(&Clock::from_account_info(clock_sysvar_acc)?)?;
+    let deadline = multisig.proposal_deadline(ix_data.expiry, current_time)?;
+
+    // A proposal must stay open until it can be executed: past the timelock, and past the
+    // challenge window too for an optimistic one
+    let mut unlock_time = current_time.saturating_add(multisig.time_lock);
+    if optimistic {
+        unlock_time = unlock_time.saturating_add(multisig.challenge_window);
+    }
+    if deadline < unlock_time {
+        return Err(ProgramError::InvalidInstructionData);
+    }
 
     let proposal = ProposalState::from_account_info(&proposal_account)?;
     proposal.new(
         ix_data.primary_seed,
-        ix_data.expiry,
+        deadline,
         ProposalStatus::Draft,
         proposal_bump,
         current_time,
         ix_data.tx_type,
+        creator.key(),
+        optimistic,
+        &candidates[..options],
+        &transfer_amounts[..options],
     );
 
+    multisig.open_proposal(optimistic)?;
+
     Ok(())
 }
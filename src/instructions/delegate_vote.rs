@@ -0,0 +1,116 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Seed,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::helper::{
+    account_checks::check_signer,
+    account_close::close_account,
+    account_init::{create_pda_account, StateDefinition},
+    utils::{load_ix_data, DataLen},
+};
+use crate::state::{
+    account_type::AccountType,
+    delegation::DelegationState,
+    member::{MemberState, Permission},
+    multisig::MultisigState,
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct DelegateVoteIxData {
+    pub duration: u64,      // Lifetime in the multisig's TimeUnit, 0 for no limit
+    pub proposal_types: u8, // Bitmask of ProposalType discriminants, 0 revokes the delegation
+    pub multisig_bump: u8,
+}
+
+impl DataLen for DelegateVoteIxData {
+    const LEN: usize = core::mem::size_of::<DelegateVoteIxData>();
+}
+
+/// Signed by a member to let another key cast their vote, replacing any earlier delegation.
+/// Delegating no proposal types revokes it and returns the delegation rent to the member.
+pub fn process_delegate_vote_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [delegator, multisig_account, delegation_acc, delegate, rent_acc, _system_program, _remaining @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(delegator)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let ix_data = unsafe { load_ix_data::<DelegateVoteIxData>(data)? };
+
+    if ix_data.proposal_types & !MultisigState::ALL_PROPOSAL_TYPES != 0
+        || delegate.key() == delegator.key()
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let multisig_state = MultisigState::from_account_info(multisig_account)?;
+    multisig_state.validate_pda(multisig_account.key(), ix_data.multisig_bump)?;
+
+    let (_, member_data) = unsafe {
+        multisig_account
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
+
+    match MemberState::find(member_data, delegator.key())? {
+        Some((_, member)) if member.has_permission(Permission::Vote) => {}
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+
+    let (pda_delegation, delegation_bump) =
+        DelegationState::find_pda(multisig_account.key(), delegator.key());
+    if pda_delegation.ne(delegation_acc.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    if ix_data.proposal_types == 0 {
+        if delegation_acc.data_is_empty() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        return close_account(delegation_acc, delegator);
+    }
+
+    if delegation_acc.data_is_empty() {
+        let rent = Rent::from_account_info(rent_acc)?;
+        let bump_bytes = [delegation_bump];
+        let signer_seeds = [
+            Seed::from(DelegationState::SEED.as_bytes()),
+            Seed::from(multisig_account.key()),
+            Seed::from(delegator.key()),
+            Seed::from(&bump_bytes[..]),
+        ];
+        create_pda_account::<DelegationState>(delegator, delegation_acc, &signer_seeds, &rent)?;
+    }
+
+    let expiry = match ix_data.duration {
+        0 => 0,
+        duration => multisig_state
+            .current_time(&Clock::get()?)?
+            .checked_add(duration)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    };
+
+    let delegation = DelegationState::from_account_info(delegation_acc)?;
+    delegation.account_type = AccountType::Delegation as u8;
+    delegation.multisig = *multisig_account.key();
+    delegation.delegator = *delegator.key();
+    delegation.delegate = *delegate.key();
+    delegation.expiry = expiry;
+    delegation.proposal_types = ix_data.proposal_types;
+    delegation.bump = delegation_bump;
+    delegation.time_unit = multisig_state.time_unit;
+    delegation._padding = [0; 4];
+
+    Ok(())
+}
@@ -3,12 +3,14 @@ use pinocchio::{
     account_info::AccountInfo,
     cpi::MAX_CPI_ACCOUNTS,
     program_error::ProgramError,
-    sysvars::{clock::Clock, Sysvar},
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
     ProgramResult,
 };
 
+use crate::helper::{account_close::shrink_account, account_init::StateDefinition};
+use crate::state::member::{MemberState, Permission};
 use crate::state::multisig::MultisigState;
-use crate::state::proposal::{ProposalState, ProposalStatus};
+use crate::state::proposal::{ProposalState, ProposalStatus, ProposalType};
 use crate::state::transaction::TransactionState;
 
 pub struct AccountRefs<'a> {
@@ -42,8 +44,11 @@ pub fn convert_accounts_to_refs<'a>(
     })
 }
 
+/// Runs an approved proposal's transaction. The multisig's rent collector follows the
+/// system program, to receive the rent of the vote storage dropped on execution; only the
+/// accounts after it are passed on to a CPI.
 pub fn process_execute_transaction_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [payer, multisig, proposal, transaction, rent, _system_program, _remaining @ ..] = accounts else {
+    let [payer, multisig, proposal, transaction, rent, _system_program, rent_collector, _remaining @ ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys)
     };
 
@@ -57,6 +62,21 @@ pub fn process_execute_transaction_instruction(accounts: &[AccountInfo], data: &
 
     let multisig_data = MultisigState::from_account_info(multisig)?;
 
+    let (_, member_data) = unsafe {
+        multisig
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
+
+    if multisig_data.rent_collector != *rent_collector.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    match MemberState::find(member_data, payer.key())? {
+        Some((_, member)) if member.has_permission(Permission::Execute) => {}
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+
     let proposal_data = ProposalState::from_account_info(proposal)?;
 
     ProposalState::validate_pda(
@@ -73,17 +93,94 @@ pub fn process_execute_transaction_instruction(accounts: &[AccountInfo], data: &
         _ => {}
     }
 
-    let yes_votes = proposal_data.yes_votes;
+    let transaction_data = TransactionState::from_account_info(transaction)?;
 
-    if yes_votes < multisig_data.min_threshold {
+    let now = multisig_data.current_time(&Clock::get()?)?;
+
+    if now > proposal_data.expiry {
         return Err(ProgramError::InvalidAccountData);
     }
 
-    if Clock::get()?.unix_timestamp > proposal_data.expiry as i64 {
+    // An unbound proposal's votes were counted without knowing the amount, so once tiers are
+    // set a transfer has to go through a proposal bound to it
+    if proposal_data.options == 0
+        && proposal_data.tx_type == ProposalType::Cpi
+        && transaction_data.transfer_amount().is_some()
+        && multisig_data.has_transfer_tiers()
+    {
         return Err(ProgramError::InvalidAccountData);
     }
 
-    let transaction_data = TransactionState::from_account_info(transaction)?;
+    if proposal_data.optimistic {
+        // No votes decide which tier a transfer falls in, so tiered transfers need a vote
+        if transaction_data.transfer_amount().is_some() && multisig_data.has_transfer_tiers() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Passes on time alone once the challenge window and then the timelock are over
+        // without a veto
+        let unlock_time = proposal_data
+            .created_time
+            .checked_add(multisig_data.challenge_window)
+            .and_then(|time| time.checked_add(multisig_data.time_lock))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        if multisig_data.vetoes(proposal_data.no_votes) || now < unlock_time {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Required approvers still have to vote yes, and the proposal only runs the
+        // transaction it is bound to
+        let voters = proposal_data.option_voters(proposal_data.option_of(transaction.key())?);
+        let yes_voters = unsafe {
+            &proposal.borrow_data_unchecked()
+                [ProposalState::LEN + voters.start * 32..ProposalState::LEN + voters.end * 32]
+        };
+        if !multisig_data.required_approvers_voted(proposal_data.tx_type, yes_voters) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    } else {
+        // Only yes votes for this transaction count, the other options of a multi-choice
+        // proposal go stale once it succeeds
+        let option = proposal_data.option_of(transaction.key())?;
+        let voters = proposal_data.option_voters(option);
+        let yes_votes = voters.len() as u8;
+
+        // Transfers are held to the tier matching the amount recorded when the proposal was
+        // bound, the same threshold its votes were counted against
+        let required_threshold = proposal_data.threshold(multisig_data, option);
+
+        if !proposal_data.reached_quorum(multisig_data.quorum) || yes_votes < required_threshold {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Recount by role against the current member list, roles may have changed since voting
+        let yes_voters = unsafe {
+            &proposal.borrow_data_unchecked()
+                [ProposalState::LEN + voters.start * 32..ProposalState::LEN + voters.end * 32]
+        };
+        let (admin_yes_votes, member_yes_votes) =
+            multisig_data.count_votes_by_role(member_data, yes_voters)?;
+        if !multisig_data.chambers_approve(admin_yes_votes, member_yes_votes) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if !multisig_data.required_approvers_voted(proposal_data.tx_type, yes_voters) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Approved proposals wait out the timelock so members can still cancel them
+        if multisig_data.time_lock > 0 {
+            let unlock_time = proposal_data
+                .approved_time
+                .checked_add(multisig_data.time_lock)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+
+            if proposal_data.approved_time == 0 || now < unlock_time {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+    }
 
     if multisig_data.transaction_index != transaction_data.transaction_index {
         return Err(ProgramError::InvalidAccountData);
@@ -100,9 +197,20 @@ pub fn process_execute_transaction_instruction(accounts: &[AccountInfo], data: &
         )
     };
 
-    TransactionState::execute(proposal_data.tx_type, account_refs)?;
-
+    // Marked up front so `TransactionState::execute` sees an approved proposal; a failed
+    // execution reverts it along with everything else
     proposal_data.status = ProposalStatus::Succeeded;
+    multisig_data.close_proposal(proposal_data.optimistic);
+
+    // The vote keys and delegate records aren't read once the proposal is decided
+    shrink_account(
+        proposal,
+        ProposalState::LEN,
+        rent_collector,
+        &Rent::from_account_info(rent)?,
+    )?;
+
+    TransactionState::execute(proposal_data.tx_type, account_refs)?;
 
     Ok(())
 }
@@ -0,0 +1,110 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::helper::{
+    account_checks::check_signer,
+    account_close::shrink_account,
+    account_init::StateDefinition,
+    utils::{load_ix_data, DataLen},
+};
+use crate::state::{
+    member::{MemberState, Permission},
+    multisig::MultisigState,
+    proposal::{ProposalState, ProposalStatus},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct FinalizeExpiredIxData {
+    pub multisig_bump: u8,
+}
+
+impl DataLen for FinalizeExpiredIxData {
+    const LEN: usize = core::mem::size_of::<FinalizeExpiredIxData>();
+}
+
+/// Permissionless crank that fails a proposal once it has expired or can no longer reach
+/// the threshold, or cleans up one its votes already failed. The vote storage is dropped
+/// with its rent going to the proposal creator, and the treasury pays the cranker the
+/// multisig's `finalize_reward` when it can afford to.
+pub fn process_finalize_expired_instruction(
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let [cranker, multisig_account, proposal_account, creator, treasury, _remaining @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(cranker)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let ix_data = unsafe { load_ix_data::<FinalizeExpiredIxData>(data)? };
+
+    let multisig_state = MultisigState::from_account_info(multisig_account)?;
+    multisig_state.validate_pda(multisig_account.key(), ix_data.multisig_bump)?;
+
+    let proposal = ProposalState::from_account_info(proposal_account)?;
+
+    ProposalState::validate_pda(
+        proposal_account.key(),
+        multisig_account.key(),
+        proposal.bump,
+        proposal.proposal_id,
+    )?;
+
+    if proposal.creator != *creator.key() || multisig_state.treasury != *treasury.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let (_, member_data) = unsafe {
+        multisig_account
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
+    let members = &member_data[..multisig_state.num_members as usize * MemberState::LEN];
+    let eligible_voters = MemberState::count_with_permission(members, Permission::Vote)?;
+
+    match proposal.status {
+        ProposalStatus::Draft | ProposalStatus::Active => {
+            let expired = multisig_state.current_time(&Clock::get()?)? > proposal.expiry;
+            let threshold = proposal.lowest_threshold(multisig_state);
+            let unreachable = !proposal.optimistic
+                && proposal.reached_quorum(multisig_state.quorum)
+                && !proposal.can_reach_threshold(eligible_voters, threshold);
+
+            if !expired && !unreachable {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            proposal.status = ProposalStatus::Failed;
+            multisig_state.close_proposal(proposal.optimistic);
+        }
+        // Already failed by its votes, only the vote storage is left to reclaim
+        ProposalStatus::Failed if proposal_account.data_len() > ProposalState::LEN => {}
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+
+    let rent = Rent::get()?;
+
+    shrink_account(proposal_account, ProposalState::LEN, creator, &rent)?;
+
+    // The reward is best effort, an underfunded treasury just skips it
+    let available = treasury
+        .lamports()
+        .saturating_sub(rent.minimum_balance(treasury.data_len()));
+    if multisig_state.finalize_reward > 0 && available >= multisig_state.finalize_reward {
+        *treasury.try_borrow_mut_lamports()? -= multisig_state.finalize_reward;
+        *cranker.try_borrow_mut_lamports()? += multisig_state.finalize_reward;
+    }
+
+    Ok(())
+}
@@ -12,16 +12,17 @@ use crate::helper::{
     account_init::{create_pda_account, StateDefinition},
     utils::{load_ix_data, DataLen},
 };
-use crate::state::{MemberRole, MultisigState};
+use crate::state::{MemberRole, MemberState, MultisigState};
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct InitMultisigIxData {
-    pub max_expiry: u64,   // 8 bytes
-    pub primary_seed: u16, // 2 bytes
-    pub min_threshold: u8, // 1 byte
-    pub num_members: u8,   // 1 byte
-    pub num_admins: u8,    // 1 byte
+    pub max_expiry: u64,               // 8 bytes
+    pub primary_seed: u16,             // 2 bytes
+    pub min_threshold: u8,             // 1 byte
+    pub num_members: u8,               // 1 byte
+    pub num_admins: u8,                // 1 byte
+    pub require_member_signatures: u8, // 1 byte, non-zero makes every initial member sign
 }
 
 impl DataLen for InitMultisigIxData {
@@ -90,6 +91,13 @@ pub fn process_init_multisig_instruction(accounts: &[AccountInfo], data: &[u8])
         ix_data,
     )?;
 
+    let (_, member_data) = unsafe {
+        multisig
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
+    multisig_account.check_invariants(member_data)?;
+
     if !treasury.data_is_empty() {
         return Err(ProgramError::AccountAlreadyInitialized);
     }
@@ -120,6 +128,17 @@ fn add_all_members(
     remaining: &[AccountInfo],
     ix_data: &InitMultisigIxData,
 ) -> ProgramResult {
+    if remaining.len() < ix_data.num_members as usize {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    // Same acceptance guarantee as invitations: every initial member proves key control
+    if ix_data.require_member_signatures != 0 {
+        for member in &remaining[..ix_data.num_members as usize] {
+            check_signer(member)?;
+        }
+    }
+
     if ix_data.num_members > 0 {
         // Calculate total size needed for all members
         let total_member_size =
@@ -153,8 +172,17 @@ fn add_all_members(
             let member_start = i * crate::state::member::MemberState::LEN;
             let member_end = member_start + crate::state::member::MemberState::LEN;
 
-            // Copy the pubkey directly
-            member_data[member_start..member_end].copy_from_slice(member.key());
+            // Admins get every permission, normal members the default set
+            let role = if i < ix_data.num_admins as usize {
+                MemberRole::Admin
+            } else {
+                MemberRole::Member
+            };
+            let new_member = MemberState {
+                pubkey: *member.key(),
+                permissions: MemberState::default_permissions(role as u8),
+            };
+            member_data[member_start..member_end].copy_from_slice(&new_member.to_bytes()?);
         }
 
         // Update counters
@@ -0,0 +1,82 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, sysvars::rent::Rent, ProgramResult,
+};
+
+use crate::helper::{
+    account_checks::check_signer,
+    account_close::sweep_excess_rent,
+    account_init::StateDefinition,
+    utils::{load_ix_data, DataLen},
+};
+use crate::state::{member::MemberState, multisig::MultisigState};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct LeaveMultisigIxData {
+    pub multisig_bump: u8,
+}
+
+impl DataLen for LeaveMultisigIxData {
+    const LEN: usize = core::mem::size_of::<LeaveMultisigIxData>();
+}
+
+/// Signed by a member to remove themselves without a proposal. The remaining member set
+/// must still satisfy the removal policy and membership invariants, and the rent freed by
+/// the member slot goes back to the leaver. Anything else the multisig account holds above
+/// its rent-exempt minimum goes to the rent collector.
+pub fn process_leave_multisig_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [member, multisig_account, rent_acc, rent_collector, _remaining @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(member)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let ix_data = unsafe { load_ix_data::<LeaveMultisigIxData>(data)? };
+
+    let multisig_state = MultisigState::from_account_info(multisig_account)?;
+    multisig_state.validate_pda(multisig_account.key(), ix_data.multisig_bump)?;
+
+    if multisig_state.rent_collector != *rent_collector.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let previous_members = multisig_state.num_members;
+    let previous_threshold = multisig_state.min_threshold;
+
+    let (_, member_area) = unsafe {
+        multisig_account
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
+
+    super::remove_member::remove_member(multisig_state, member_area, member.key().as_ref())?;
+
+    multisig_state.apply_removal_policy(previous_members, previous_threshold)?;
+    multisig_state.check_invariants(member_area)?;
+
+    let previous_size = multisig_account.data_len();
+    let new_size = MultisigState::LEN + multisig_state.num_members as usize * MemberState::LEN;
+    multisig_account.resize(new_size)?;
+
+    // Unlike proposal-driven removals, the rent of the freed slot goes to the leaver rather
+    // than the rent collector
+    let rent = Rent::from_account_info(rent_acc)?;
+    let excess = multisig_account
+        .lamports()
+        .saturating_sub(rent.minimum_balance(new_size));
+    let refund = rent
+        .minimum_balance(previous_size)
+        .saturating_sub(rent.minimum_balance(new_size))
+        .min(excess);
+
+    *multisig_account.try_borrow_mut_lamports()? -= refund;
+    *member.try_borrow_mut_lamports()? += refund;
+
+    sweep_excess_rent(multisig_account, rent_collector, &rent)?;
+
+    Ok(())
+}
@@ -1,16 +1,29 @@
+pub mod accept_membership;
 pub mod add_member;
+pub mod cancel_proposal;
 pub mod create_proposal;
 pub mod create_transaction;
+pub mod delegate_vote;
 pub mod execute_transaction;
+pub mod finalize_expired;
 pub mod init_multisig;
+pub mod leave_multisig;
+pub mod register_session;
 pub mod remove_member;
+pub mod update_member_role;
 pub mod update_members;
 pub mod update_multisig;
 pub mod vote;
+pub use accept_membership::*;
+pub use cancel_proposal::*;
 pub use create_proposal::*;
 pub use create_transaction::*;
+pub use delegate_vote::*;
 pub use execute_transaction::*;
+pub use finalize_expired::*;
 pub use init_multisig::*;
+pub use leave_multisig::*;
+pub use register_session::*;
 pub use update_members::*;
 pub use update_multisig::*;
 pub use vote::*;
@@ -24,6 +37,12 @@ pub enum MultisigInstructions {
     Vote = 3,
     CreateTransaction = 4,
     ExecuteTransaction = 5,
+    AcceptMembership = 6,
+    LeaveMultisig = 7,
+    CancelProposal = 8,
+    FinalizeExpired = 9,
+    DelegateVote = 10,
+    RegisterSession = 11,
 }
 
 impl TryFrom<&u8> for MultisigInstructions {
@@ -37,6 +56,12 @@ impl TryFrom<&u8> for MultisigInstructions {
             3 => Ok(MultisigInstructions::Vote),
             4 => Ok(MultisigInstructions::CreateTransaction),
             5 => Ok(MultisigInstructions::ExecuteTransaction),
+            6 => Ok(MultisigInstructions::AcceptMembership),
+            7 => Ok(MultisigInstructions::LeaveMultisig),
+            8 => Ok(MultisigInstructions::CancelProposal),
+            9 => Ok(MultisigInstructions::FinalizeExpired),
+            10 => Ok(MultisigInstructions::DelegateVote),
+            11 => Ok(MultisigInstructions::RegisterSession),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
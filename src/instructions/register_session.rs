@@ -0,0 +1,112 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Seed,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::helper::{
+    account_checks::check_signer,
+    account_close::close_account,
+    account_init::{create_pda_account, StateDefinition},
+    utils::{load_ix_data, DataLen},
+};
+use crate::state::{
+    account_type::AccountType,
+    member::{MemberState, Permission},
+    multisig::MultisigState,
+    session::SessionState,
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct RegisterSessionIxData {
+    pub duration: u64, // Lifetime in the multisig's TimeUnit, 0 revokes the session
+    pub multisig_bump: u8,
+}
+
+impl DataLen for RegisterSessionIxData {
+    const LEN: usize = core::mem::size_of::<RegisterSessionIxData>();
+}
+
+/// Signed by a member's main key to let a session key vote for them until it expires,
+/// replacing any earlier session. A zero duration revokes it and returns the session rent
+/// to the member.
+pub fn process_register_session_instruction(
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let [member, multisig_account, session_acc, session_key, rent_acc, _system_program, _remaining @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_signer(member)?;
+
+    if multisig_account.owner() != &crate::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let ix_data = unsafe { load_ix_data::<RegisterSessionIxData>(data)? };
+
+    if session_key.key() == member.key() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let multisig_state = MultisigState::from_account_info(multisig_account)?;
+    multisig_state.validate_pda(multisig_account.key(), ix_data.multisig_bump)?;
+
+    let (_, member_data) = unsafe {
+        multisig_account
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
+
+    match MemberState::find(member_data, member.key())? {
+        Some((_, record)) if record.has_permission(Permission::Vote) => {}
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+
+    let (pda_session, session_bump) = SessionState::find_pda(multisig_account.key(), member.key());
+    if pda_session.ne(session_acc.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    if ix_data.duration == 0 {
+        if session_acc.data_is_empty() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        return close_account(session_acc, member);
+    }
+
+    if session_acc.data_is_empty() {
+        let rent = Rent::from_account_info(rent_acc)?;
+        let bump_bytes = [session_bump];
+        let signer_seeds = [
+            Seed::from(SessionState::SEED.as_bytes()),
+            Seed::from(multisig_account.key()),
+            Seed::from(member.key()),
+            Seed::from(&bump_bytes[..]),
+        ];
+        create_pda_account::<SessionState>(member, session_acc, &signer_seeds, &rent)?;
+    }
+
+    let expiry = multisig_state
+        .current_time(&Clock::get()?)?
+        .checked_add(ix_data.duration)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let session = SessionState::from_account_info(session_acc)?;
+    session.account_type = AccountType::Session as u8;
+    session.multisig = *multisig_account.key();
+    session.member = *member.key();
+    session.session_key = *session_key.key();
+    session.expiry = expiry;
+    session.bump = session_bump;
+    session.time_unit = multisig_state.time_unit;
+    session._padding = [0; 5];
+
+    Ok(())
+}
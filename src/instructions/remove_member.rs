@@ -1,32 +1,20 @@
-use crate::helper::account_init::StateDefinition;
 use crate::state::{member::MemberState, multisig::MultisigState};
-use pinocchio::{
-    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
-};
-
-pub(crate) fn remove_member(accounts: &[&AccountInfo], data: &[u8]) -> ProgramResult {
-    let [_, multisig_account, _rent_acc, _system_program_acc, _remaining @ ..] = accounts else {
-        return Err(ProgramError::NotEnoughAccountKeys);
-    };
-
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+
+/// Removes a member from `member_area`, zeroing the freed trailing slot
+pub(crate) fn remove_member(
+    multisig_state: &mut MultisigState,
+    member_area: &mut [u8],
+    data: &[u8],
+) -> ProgramResult {
     if data.len() < 32 {
         return Err(ProgramError::InvalidInstructionData);
     }
 
-    let mut multisig_state = MultisigState::from_account_info(multisig_account)?;
     if multisig_state.num_members == 0 {
         return Err(ProgramError::InvalidInstructionData);
     }
 
-    // member area slice (bytes after header)
-    let header_len = MultisigState::LEN;
-    let member_area = unsafe {
-        &mut multisig_account
-            .borrow_mut_data_unchecked()
-            .split_at_mut_unchecked(header_len)
-            .1
-    };
-
     let total_members = multisig_state.num_members as usize;
     let member_len = MemberState::LEN;
 
@@ -40,15 +28,11 @@ pub(crate) fn remove_member(accounts: &[&AccountInfo], data: &[u8]) -> ProgramRe
     let member_to_remove = Pubkey::from(pk_bytes);
 
     // find index
-    let mut found_idx: Option<usize> = None;
-    for (idx, chunk) in member_area.chunks_exact(member_len).enumerate() {
-        let m = MemberState::from_bytes(chunk)?;
-        if m.pubkey == member_to_remove {
-            found_idx = Some(idx);
-            break;
-        }
-    }
-    let idx = found_idx.ok_or(ProgramError::InvalidInstructionData)?;
+    let (idx, _) = MemberState::find(
+        &member_area[..total_members * member_len],
+        &member_to_remove,
+    )?
+    .ok_or(ProgramError::InvalidInstructionData)?;
 
     let admin_count = multisig_state.admin_counter as usize;
     let is_admin = idx < admin_count;
@@ -112,11 +96,5 @@ pub(crate) fn remove_member(accounts: &[&AccountInfo], data: &[u8]) -> ProgramRe
         .checked_sub(1)
         .ok_or(ProgramError::ArithmeticOverflow)?;
 
-    let new_size = multisig_account
-        .data_len()
-        .checked_sub(member_len)
-        .ok_or(ProgramError::InvalidAccountData)?;
-    multisig_account.resize(new_size)?;
-
     Ok(())
 }
@@ -0,0 +1,209 @@
+use crate::helper::account_init::StateDefinition;
+use crate::state::{
+    member::MemberState,
+    multisig::MultisigState,
+    proposal::{ProposalState, ProposalStatus},
+};
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+fn parse_pubkey(data: &[u8]) -> Result<Pubkey, ProgramError> {
+    if data.len() < 32 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let mut pk_bytes = [0u8; 32];
+    pk_bytes.copy_from_slice(&data[..32]);
+    Ok(Pubkey::from(pk_bytes))
+}
+
+fn swap_members(member_area: &mut [u8], a: usize, b: usize) {
+    if a == b {
+        return;
+    }
+    let a = a * MemberState::LEN;
+    let b = b * MemberState::LEN;
+    for j in 0..MemberState::LEN {
+        member_area.swap(a + j, b + j);
+    }
+}
+
+fn current_members<'a>(
+    multisig_state: &MultisigState,
+    member_area: &'a mut [u8],
+) -> Result<&'a mut [u8], ProgramError> {
+    let members_len = multisig_state.num_members as usize * MemberState::LEN;
+    if member_area.len() < members_len {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(&mut member_area[..members_len])
+}
+
+/// Moves a normal member into the admin section and grants the admin permission set
+pub(crate) fn promote_member(
+    multisig_state: &mut MultisigState,
+    member_area: &mut [u8],
+    data: &[u8],
+) -> ProgramResult {
+    let member_to_promote = parse_pubkey(data)?;
+    let member_area = current_members(multisig_state, member_area)?;
+
+    let (idx, mut member) = MemberState::find(member_area, &member_to_promote)?
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let admin_count = multisig_state.admin_counter as usize;
+    if idx < admin_count {
+        // Already an admin
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // The first normal member slot becomes the new last admin slot
+    swap_members(member_area, idx, admin_count);
+
+    member.permissions |= MemberState::ALL_PERMISSIONS;
+    let start = admin_count * MemberState::LEN;
+    member_area[start..start + MemberState::LEN].copy_from_slice(&member.to_bytes()?);
+
+    multisig_state.admin_counter = multisig_state
+        .admin_counter
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Moves an admin into the normal member section and drops permissions normal members lack
+pub(crate) fn demote_member(
+    multisig_state: &mut MultisigState,
+    member_area: &mut [u8],
+    data: &[u8],
+) -> ProgramResult {
+    let member_to_demote = parse_pubkey(data)?;
+    let member_area = current_members(multisig_state, member_area)?;
+
+    let (idx, mut member) = MemberState::find(member_area, &member_to_demote)?
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let admin_count = multisig_state.admin_counter as usize;
+    if idx >= admin_count {
+        // Not an admin
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // The last admin slot becomes the first normal member slot
+    let last_admin_idx = admin_count - 1;
+    swap_members(member_area, idx, last_admin_idx);
+
+    member.permissions &= MemberState::DEFAULT_MEMBER_PERMISSIONS;
+    let start = last_admin_idx * MemberState::LEN;
+    member_area[start..start + MemberState::LEN].copy_from_slice(&member.to_bytes()?);
+
+    multisig_state.admin_counter = multisig_state
+        .admin_counter
+        .checked_sub(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Replaces a member's key in place, keeping its position (role) and permissions, along
+/// with any required approver slot it holds. Its votes and delegate records on open
+/// proposals move to the new key, so every open proposal of the multisig must be among
+/// `accounts`.
+pub(crate) fn rotate_member_key(
+    multisig_account: &AccountInfo,
+    multisig_state: &mut MultisigState,
+    member_area: &mut [u8],
+    data: &[u8],
+    accounts: &[&AccountInfo],
+) -> ProgramResult {
+    if data.len() < 64 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let current_key = parse_pubkey(&data[..32])?;
+    let new_key = parse_pubkey(&data[32..64])?;
+
+    let member_area = current_members(multisig_state, member_area)?;
+
+    if MemberState::find(member_area, &new_key)?.is_some() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (idx, _) = MemberState::find(member_area, &current_key)?
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let start = idx * MemberState::LEN;
+    member_area[start..start + 32].copy_from_slice(new_key.as_ref());
+
+    for approver in multisig_state.required_approvers.iter_mut() {
+        if *approver == current_key {
+            *approver = new_key;
+        }
+    }
+
+    rotate_open_votes(
+        multisig_account,
+        multisig_state,
+        accounts,
+        &current_key,
+        &new_key,
+    )
+}
+
+/// Moves `current_key`'s votes to `new_key` on the open proposals of the multisig found in
+/// `accounts`, other accounts are skipped. Left behind, the old votes would stop counting
+/// by role while the new key could vote a second time.
+fn rotate_open_votes(
+    multisig_account: &AccountInfo,
+    multisig_state: &MultisigState,
+    accounts: &[&AccountInfo],
+    current_key: &Pubkey,
+    new_key: &Pubkey,
+) -> ProgramResult {
+    let mut rotated = 0u16;
+
+    for (idx, account) in accounts.iter().enumerate() {
+        if account.owner() != &crate::ID
+            || account.data_len() < ProposalState::LEN
+            || accounts[..idx]
+                .iter()
+                .any(|seen| seen.key() == account.key())
+        {
+            continue;
+        }
+
+        let (header, votes) = unsafe {
+            account
+                .borrow_mut_data_unchecked()
+                .split_at_mut_unchecked(ProposalState::LEN)
+        };
+        let Ok(proposal) = ProposalState::from_bytes(header) else {
+            continue;
+        };
+
+        let is_proposal = ProposalState::validate_pda(
+            account.key(),
+            multisig_account.key(),
+            proposal.bump,
+            proposal.proposal_id,
+        )
+        .is_ok();
+        let open = matches!(
+            proposal.status,
+            ProposalStatus::Draft | ProposalStatus::Active
+        );
+        if !is_proposal || !open {
+            continue;
+        }
+
+        proposal.rotate_voter(votes, current_key, new_key)?;
+        rotated += 1;
+    }
+
+    if rotated != multisig_state.open_proposals {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    Ok(())
+}
@@ -1,30 +1,148 @@
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, sysvars::rent::Rent, ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
 
 use crate::helper::utils::{load_ix_data, DataLen};
+use crate::helper::{account_close::sweep_excess_rent, account_init::StateDefinition};
+use crate::state::{member::MemberState, multisig::MultisigState};
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct UpdateMemberIxData {
-    pub operation: u8,         // 1 for add, 2 for remove
-    pub member_data: [u8; 33], // 32 bytes pubkey + 1 byte role (for add) or just 32 bytes pubkey (for remove)
+    pub operation: u8, // 1 add, 2 remove, 3 promote, 4 demote, 5 rotate key
+    // add: 32 bytes pubkey + 1 byte role + 1 byte permissions
+    // remove / promote / demote: 32 bytes pubkey
+    // rotate key: 32 bytes current pubkey + 32 bytes new pubkey
+    pub member_data: [u8; 64],
 }
 
 impl DataLen for UpdateMemberIxData {
     const LEN: usize = core::mem::size_of::<UpdateMemberIxData>();
 }
 
+/// Applies one or more back-to-back `UpdateMemberIxData` records as a single batch.
+/// The account is grown once up front for all adds and trimmed once at the end, and
+/// the membership invariants are only checked against the final member set.
+///
+/// When the multisig has an `invitation_ttl`, adds create invitation accounts instead,
+/// taken in order from the accounts after the rent collector, which `execute_transaction`
+/// places after the system program and which receives the rent freed by removals. Key
+/// rotations need every open proposal of the multisig among those accounts to move the
+/// old key's votes.
 pub(crate) fn process_update_member(accounts: &[&AccountInfo], data: &[u8]) -> ProgramResult {
-    let ix_data = unsafe { load_ix_data::<UpdateMemberIxData>(data)? };
+    let [payer, multisig_account, _proposal, _transaction, rent_acc, _system_program_acc, rent_collector, remaining @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
 
-    match ix_data.operation {
-        1 => {
-            // Add member - pass the member_data as the data parameter
-            super::add_member::add_member(accounts, &ix_data.member_data)
+    if data.is_empty() || data.len() % UpdateMemberIxData::LEN != 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let multisig_state = MultisigState::from_account_info(multisig_account)?;
+    let previous_members = multisig_state.num_members;
+    let previous_threshold = multisig_state.min_threshold;
+
+    let invite = multisig_state.invitation_ttl > 0;
+
+    let mut num_adds = 0usize;
+    for record in data.chunks_exact(UpdateMemberIxData::LEN) {
+        let ix_data = unsafe { load_ix_data::<UpdateMemberIxData>(record)? };
+        if ix_data.operation == 1 && !invite {
+            num_adds += 1;
+        }
+    }
+
+    // Grow once so every add in the batch has a free slot
+    let current_size = multisig_account.data_len();
+    let peak_size =
+        MultisigState::LEN + (multisig_state.num_members as usize + num_adds) * MemberState::LEN;
+
+    if peak_size > current_size {
+        let rent = Rent::from_account_info(rent_acc)?;
+        let rent_diff = rent
+            .minimum_balance(peak_size)
+            .saturating_sub(multisig_account.lamports());
+
+        if rent_diff > 0 {
+            Transfer {
+                from: payer,
+                to: multisig_account,
+                lamports: rent_diff,
+            }
+            .invoke()?;
         }
-        2 => {
-            // Remove member - pass only the first 32 bytes (pubkey) as the data parameter
-            super::remove_member::remove_member(accounts, &ix_data.member_data[..32])
+
+        multisig_account.resize(peak_size)?;
+    }
+
+    let (_, member_area) = unsafe {
+        multisig_account
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
+
+    let mut invitation_accounts = remaining.iter();
+
+    for record in data.chunks_exact(UpdateMemberIxData::LEN) {
+        let ix_data = unsafe { load_ix_data::<UpdateMemberIxData>(record)? };
+        let member_data = &ix_data.member_data;
+
+        match ix_data.operation {
+            1 if invite => {
+                let invitation_acc = invitation_accounts
+                    .next()
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                super::accept_membership::invite_member(
+                    payer,
+                    multisig_account,
+                    multisig_state,
+                    member_area,
+                    invitation_acc,
+                    rent_acc,
+                    member_data,
+                )?
+            }
+            1 => super::add_member::add_member(multisig_state, member_area, member_data)?,
+            2 => super::remove_member::remove_member(
+                multisig_state,
+                member_area,
+                &member_data[..32],
+            )?,
+            3 => super::update_member_role::promote_member(
+                multisig_state,
+                member_area,
+                &member_data[..32],
+            )?,
+            4 => super::update_member_role::demote_member(
+                multisig_state,
+                member_area,
+                &member_data[..32],
+            )?,
+            5 => super::update_member_role::rotate_member_key(
+                multisig_account,
+                multisig_state,
+                member_area,
+                member_data,
+                remaining,
+            )?,
+            _ => return Err(ProgramError::InvalidInstructionData),
         }
-        _ => Err(ProgramError::InvalidInstructionData),
     }
+
+    multisig_state.apply_removal_policy(previous_members, previous_threshold)?;
+    multisig_state.check_invariants(member_area)?;
+
+    // Trim any slots freed by removals and hand their rent to the rent collector
+    let final_size = MultisigState::LEN + multisig_state.num_members as usize * MemberState::LEN;
+    if final_size < multisig_account.data_len() {
+        multisig_account.resize(final_size)?;
+
+        let rent = Rent::from_account_info(rent_acc)?;
+        sweep_excess_rent(multisig_account, rent_collector, &rent)?;
+    }
+
+    Ok(())
 }
@@ -12,14 +12,29 @@ use crate::helper::{
     account_init::{create_pda_account, StateDefinition},
     utils::{load_ix_data, DataLen},
 };
-use crate::state::MultisigState;
+use crate::state::{MultisigState, ProposalType, RemovalPolicy, TimeUnit};
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct UpdateMultisigIxData {
-    pub value: u64,      // For spending limit and stale transaction index
-    pub update_type: u8, // 1 for update threshold, 2 for update spending limit, 3 for stale transaction index
-    pub threshold: u8,   // For threshold updates
+    pub value: u64, // For update types 2 to 5, 7 to 9, 11 to 14 and 17
+    // 1 for update threshold, 2 for update spending limit, 3 for stale transaction index,
+    // 4 for removal policy, 5 for invitation ttl (0 adds members directly),
+    // 6 for rent collector (the first account after the current rent collector),
+    // 7 for time lock (below the max expiry),
+    // 8 for time unit (0 unix timestamp, 1 slot, only while no other proposal is open,
+    // converts the stored durations),
+    // 9 for finalize reward (lamports),
+    // 10 for reject threshold (0 disables it, only while no optimistic proposal is open),
+    // 11 for the threshold of the ProposalType in value (0 falls back to the base threshold),
+    // 12 for admin (threshold) and member (value) chamber thresholds (0 for none),
+    // 13 for the threshold of system transfers below value lamports (0 removes the tier),
+    // 14 for the ProposalType bitmask in value that needs the first account after the rent
+    // collector to vote yes (0 drops the requirement), 15 for quorum (0 disables it),
+    // 16 for threshold as a percentage of the members (0 goes back to the min threshold),
+    // 17 for the optimistic proposal challenge window (0 disables them)
+    pub update_type: u8,
+    pub threshold: u8, // For threshold updates (types 1, 10 to 13, 15 and 16)
 }
 
 impl DataLen for UpdateMultisigIxData {
@@ -27,7 +42,9 @@ impl DataLen for UpdateMultisigIxData {
 }
 
 pub(crate) fn process_update_multisig(accounts: &[&AccountInfo], data: &[u8]) -> ProgramResult {
-    let [payer, multisig, _remaining @ ..] = accounts else {
+    let [payer, multisig, _proposal, _transaction, _rent, _system_program, _rent_collector, remaining @ ..] =
+        accounts
+    else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
@@ -43,8 +60,58 @@ pub(crate) fn process_update_multisig(accounts: &[&AccountInfo], data: &[u8]) ->
         1 => multisig_state.update_threshold(ix_data.threshold),
         2 => multisig_state.update_spending_limit(ix_data.value),
         3 => multisig_state.update_stale_transaction_index(ix_data.value),
+        4 => {
+            let policy =
+                u8::try_from(ix_data.value).map_err(|_| ProgramError::InvalidInstructionData)?;
+            multisig_state.update_removal_policy(RemovalPolicy::try_from(&policy)?)
+        }
+        5 => multisig_state.update_invitation_ttl(ix_data.value),
+        6 => {
+            let rent_collector = remaining
+                .first()
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            multisig_state.update_rent_collector(rent_collector.key())
+        }
+        7 => multisig_state.update_time_lock(ix_data.value),
+        8 => {
+            let time_unit =
+                u8::try_from(ix_data.value).map_err(|_| ProgramError::InvalidInstructionData)?;
+            multisig_state.update_time_unit(TimeUnit::try_from(&time_unit)?)?
+        }
+        9 => multisig_state.update_finalize_reward(ix_data.value),
+        10 => multisig_state.update_reject_threshold(ix_data.threshold)?,
+        11 => {
+            let tx_type =
+                u8::try_from(ix_data.value).map_err(|_| ProgramError::InvalidInstructionData)?;
+            multisig_state
+                .update_type_threshold(ProposalType::try_from(&tx_type)?, ix_data.threshold)
+        }
+        12 => {
+            let member_threshold =
+                u8::try_from(ix_data.value).map_err(|_| ProgramError::InvalidInstructionData)?;
+            multisig_state.update_chamber_thresholds(ix_data.threshold, member_threshold)
+        }
+        13 => multisig_state.update_transfer_tier(ix_data.value, ix_data.threshold)?,
+        14 => {
+            let approver = remaining
+                .first()
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let types =
+                u8::try_from(ix_data.value).map_err(|_| ProgramError::InvalidInstructionData)?;
+            multisig_state.update_required_approver(approver.key(), types)?
+        }
+        15 => multisig_state.update_quorum(ix_data.threshold),
+        16 => multisig_state.update_threshold_percent(ix_data.threshold),
+        17 => multisig_state.update_challenge_window(ix_data.value),
         _ => return Err(ProgramError::InvalidInstructionData),
     }
 
+    let (_, member_data) = unsafe {
+        multisig
+            .borrow_mut_data_unchecked()
+            .split_at_mut_unchecked(MultisigState::LEN)
+    };
+    multisig_state.check_invariants(member_data)?;
+
     Ok(())
 }
@@ -1,9 +1,23 @@
-use crate::helper::account_init::StateDefinition;
-use crate::state::{member::MemberState, multisig::MultisigState, proposal::ProposalState};
+use crate::helper::{
+    account_checks::check_signer,
+    account_init::{create_pda_account, StateDefinition},
+    ed25519::load_signed_message,
+};
+use crate::state::{
+    account_type::AccountType,
+    delegation::DelegationState,
+    member::{MemberState, Permission},
+    multisig::MultisigState,
+    proposal::{ProposalState, ProposalStatus, ProposalType, VoteChoice},
+    session::SessionState,
+    vote_nonce::VoteNonceState,
+};
 use pinocchio::{
     account_info::AccountInfo,
+    instruction::Seed,
     program_error::ProgramError,
-    sysvars::{rent::Rent, Sysvar},
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, instructions::INSTRUCTIONS_ID, rent::Rent, Sysvar},
     ProgramResult,
 };
 use pinocchio_system::instructions::Transfer;
@@ -15,9 +29,14 @@ use crate::helper::utils::DataLen;
 pub struct VoteIxData {
     pub multisig_bump: u8,
     pub proposal_bump: u8,
-    pub vote: u8,
+    pub vote: u8,   // 0 no, 1 yes, 2 abstain, see VoteChoice
+    pub option: u8, // Candidate a yes vote picks on a multi-choice proposal, 0 otherwise
 }
 
+/// Length of the message a member signs to have their vote relayed: the multisig, the
+/// proposal, the vote, the option and a little-endian u64 nonce above the last one they used
+pub const SIGNED_VOTE_LEN: usize = 32 + 32 + 1 + 1 + 8;
+
 impl DataLen for VoteIxData {
     const LEN: usize = core::mem::size_of::<VoteIxData>();
 }
@@ -31,6 +50,7 @@ impl VoteIxData {
             multisig_bump: data[0],
             proposal_bump: data[1],
             vote: data[2],
+            option: data[3],
         })
     }
 
@@ -39,14 +59,17 @@ impl VoteIxData {
         bytes[0] = self.multisig_bump;
         bytes[1] = self.proposal_bump;
         bytes[2] = self.vote;
+        bytes[3] = self.option;
         Ok(bytes)
     }
 }
 
 pub fn process_vote_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [voter, multisig_account, proposal_account, _remaining @ ..] = accounts else {
+    let [voter, multisig_account, proposal_account, remaining @ ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
+
+    check_signer(voter)?;
     let ix_data = VoteIxData::from_bytes(data)?;
 
     let multisig_header = MultisigState::from_account_info(multisig_account)?;
@@ -65,18 +88,10 @@ pub fn process_vote_instruction(accounts: &[AccountInfo], data: &[u8]) -> Progra
             .split_at_mut_unchecked(MultisigState::LEN)
     };
 
-    let mut member_exists: Option<MemberState> = None;
-    for (_, m) in members.chunks_exact(MemberState::LEN).enumerate() {
-        let member = MemberState::from_bytes(m)?;
-        if member.pubkey == *voter.key() {
-            member_exists = Some(member);
-            break;
-        }
-    }
-
-    if member_exists.is_none() {
-        return Err(ProgramError::InvalidInstructionData);
-    }
+    let eligible_voters = MemberState::count_with_permission(
+        &members[..multisig_header.num_members as usize * MemberState::LEN],
+        Permission::Vote,
+    )?;
 
     let (proposal, votes) = unsafe {
         proposal_account
@@ -93,64 +108,63 @@ pub fn process_vote_instruction(accounts: &[AccountInfo], data: &[u8]) -> Progra
         proposal.proposal_id,
     )?;
 
-    let mut voted = false;
-    let mut vote_index = 0;
-    for (i, vote) in votes.chunks_exact(32).enumerate() {
-        if let Some(member) = member_exists {
-            if vote == member.pubkey.as_ref() {
-                voted = true;
-                vote_index = i;
-                break;
-            }
+    match proposal.status {
+        ProposalStatus::Draft | ProposalStatus::Active => {}
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+
+    let clock = Clock::get()?;
+    let now = multisig_header.current_time(&clock)?;
+    if now > proposal.expiry {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // A delegate or session key passes its delegation or session after the rent and system
+    // program accounts. Delegates vote as their delegator and are recorded next to the vote,
+    // session keys simply stand in for their member. A relayer instead passes the
+    // instructions sysvar and the member's vote nonce account, and pays for the vote.
+    let (vote_key, delegate) = match remaining.get(2) {
+        Some(instructions) if instructions.key() == &INSTRUCTIONS_ID => {
+            let nonce_acc = remaining.get(3).ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let member = resolve_signed_vote(
+                instructions,
+                nonce_acc,
+                voter,
+                multisig_account,
+                proposal_account,
+                &ix_data,
+            )?;
+            (member, None)
+        }
+        Some(authority) => {
+            resolve_vote_authority(authority, voter, multisig_account, proposal.tx_type, &clock)?
         }
+        None => (*voter.key(), None),
+    };
+
+    match MemberState::find(members, &vote_key)? {
+        Some((_, member)) if member.has_permission(Permission::Vote) => {}
+        _ => return Err(ProgramError::InvalidInstructionData),
     }
 
-    if voted {
-        // Swap and update the vote count
-        if ix_data.vote == 1 {
-            // replace the last yes vote with current vote and reduce the yes vote count and increase the no vote count
-            let vote_start = vote_index * 32;
-            let swap_start = (proposal.yes_votes as usize) * 32;
-
-            if vote_start != swap_start {
-                // Use temporary variable to avoid borrowing issues
-                let mut temp_vote = [0u8; 32];
-                temp_vote.copy_from_slice(&votes[vote_start..vote_start + 32]);
-                let swap_data = votes[swap_start..swap_start + 32].to_vec();
-                votes[vote_start..vote_start + 32].copy_from_slice(&swap_data);
-                votes[swap_start..swap_start + 32].copy_from_slice(&temp_vote);
-            }
+    let choice = VoteChoice::try_from(&ix_data.vote)?;
 
-            proposal.yes_votes += 1;
-            proposal.no_votes -= 1;
-        } else {
-            // replace the last no vote with current vote and increase the yes vote count and reduce the no vote count
-            let vote_start = vote_index * 32;
-            let swap_start = (proposal.no_votes + proposal.yes_votes - 1) as usize * 32;
-
-            if vote_start != swap_start {
-                // Use temporary variable to avoid borrowing issues
-                let mut temp_vote = [0u8; 32];
-                temp_vote.copy_from_slice(&votes[vote_start..vote_start + 32]);
-                let swap_data = votes[swap_start..swap_start + 32].to_vec();
-                votes[vote_start..vote_start + 32].copy_from_slice(&swap_data);
-                votes[swap_start..swap_start + 32].copy_from_slice(&temp_vote);
-            }
+    let votes_len = proposal.participation() as usize * 32;
+    let delegations_len = proposal.delegations as usize * 64;
+    let voted = votes[..votes_len]
+        .chunks_exact(32)
+        .any(|vote| vote == vote_key.as_ref());
+    let new_delegation =
+        delegate.is_some() && proposal.delegation_index(votes, &vote_key).is_none();
 
-            proposal.yes_votes -= 1;
-            proposal.no_votes += 1;
-        }
+    // Room for a new vote key and delegate record
+    let growth = if voted { 0 } else { 32 } + if new_delegation { 64 } else { 0 };
 
-        unsafe {
-            proposal_account.borrow_mut_data_unchecked()[..ProposalState::LEN]
-                .copy_from_slice(proposal.to_bytes().as_ref());
-            proposal_account.borrow_mut_data_unchecked()[ProposalState::LEN..]
-                .copy_from_slice(&votes);
-        }
-    } else {
-        // Increase the size of the account to add new vote
-        let new_size = proposal_account.data_len() + 32;
-        let rent_diff = Rent::get()?.minimum_balance(new_size) - proposal_account.lamports();
+    if growth > 0 {
+        let new_size = proposal_account.data_len() + growth;
+        let rent_diff = Rent::get()?
+            .minimum_balance(new_size)
+            .saturating_sub(proposal_account.lamports());
 
         if rent_diff > 0 {
             Transfer {
@@ -162,48 +176,185 @@ pub fn process_vote_instruction(accounts: &[AccountInfo], data: &[u8]) -> Progra
         }
 
         proposal_account.resize(new_size)?;
-        let (new_proposal, new_votes) = unsafe {
-            proposal_account
-                .borrow_mut_data_unchecked()
-                .split_at_mut_unchecked(ProposalState::LEN)
-        };
-
-        let mut new_proposal_data = ProposalState::from_bytes(new_proposal)?;
-
-        if let Some(member) = member_exists {
-            let last_vote_start = (proposal.yes_votes + proposal.no_votes) as usize * 32;
-
-            new_votes[last_vote_start..last_vote_start + 32]
-                .copy_from_slice(member.pubkey.as_ref());
-
-            if ix_data.vote == 1 {
-                if proposal.no_votes > 0 {
-                    let no_start = proposal.no_votes as usize * 32;
-                    // Swap the new vote (at the end) with the first no vote
-                    if last_vote_start != no_start {
-                        // Use temporary variables to avoid borrowing issues
-                        let mut temp_vote = [0u8; 32];
-                        temp_vote.copy_from_slice(&new_votes[no_start..no_start + 32]);
-                        let new_vote_data =
-                            new_votes[last_vote_start..last_vote_start + 32].to_vec();
-                        new_votes[no_start..no_start + 32].copy_from_slice(&new_vote_data);
-                        new_votes[last_vote_start..last_vote_start + 32]
-                            .copy_from_slice(&temp_vote);
-                    }
-                }
-                new_proposal_data.yes_votes += 1;
-            } else {
-                // Add the new no vote
-                new_proposal_data.no_votes += 1;
+    }
+
+    let votes = unsafe { &mut proposal_account.borrow_mut_data_unchecked()[ProposalState::LEN..] };
+
+    // The delegate records stay right after the vote keys
+    if !voted {
+        votes.copy_within(votes_len..votes_len + delegations_len, votes_len + 32);
+    }
+
+    // Votes are stored yes first, by option, then no, then abstain, so the role tallies can
+    // read each option's yes voters
+    proposal.record_vote(votes, &vote_key, choice, ix_data.option)?;
+
+    if let Some(delegate) = delegate {
+        proposal.record_delegate(votes, &vote_key, &delegate)?;
+    }
+
+    update_outcome(
+        &mut proposal,
+        multisig_header,
+        members,
+        votes,
+        eligible_voters,
+        now,
+    )?;
+
+    if proposal.status == ProposalStatus::Failed {
+        multisig_header.close_proposal(proposal.optimistic);
+    }
+
+    unsafe {
+        proposal_account.borrow_mut_data_unchecked()[..ProposalState::LEN]
+            .copy_from_slice(proposal.to_bytes().as_ref());
+    }
+
+    Ok(())
+}
+
+/// Key whose vote `signer` casts through `authority`, either a session PDA or a delegation
+/// PDA told apart by its `AccountType`, along with the delegate to record for delegated votes
+fn resolve_vote_authority(
+    authority: &AccountInfo,
+    signer: &AccountInfo,
+    multisig_account: &AccountInfo,
+    tx_type: ProposalType,
+    clock: &Clock,
+) -> Result<(Pubkey, Option<Pubkey>), ProgramError> {
+    let account_type = unsafe { authority.borrow_data_unchecked() }
+        .first()
+        .ok_or(ProgramError::InvalidAccountData)?;
+
+    match AccountType::try_from(account_type)? {
+        AccountType::Session => {
+            let session = SessionState::from_account_info(authority)?;
+            session.validate_pda(authority.key())?;
+
+            if session.multisig != *multisig_account.key()
+                || !session.allows(signer.key(), clock)?
+            {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            Ok((session.member, None))
+        }
+        AccountType::Delegation => {
+            let delegation = DelegationState::from_account_info(authority)?;
+            delegation.validate_pda(authority.key())?;
+
+            if delegation.multisig != *multisig_account.key()
+                || !delegation.allows(signer.key(), tx_type, clock)?
+            {
+                return Err(ProgramError::InvalidAccountData);
             }
+
+            Ok((delegation.delegator, Some(*signer.key())))
         }
+    }
+}
+
+/// Member whose ed25519-signed vote precedes this instruction, after checking the signed
+/// message matches the vote being cast and moving the member's nonce past it
+fn resolve_signed_vote(
+    instructions: &AccountInfo,
+    nonce_acc: &AccountInfo,
+    relayer: &AccountInfo,
+    multisig_account: &AccountInfo,
+    proposal_account: &AccountInfo,
+    ix_data: &VoteIxData,
+) -> Result<Pubkey, ProgramError> {
+    let (member, message) = load_signed_message::<SIGNED_VOTE_LEN>(instructions)?;
+
+    if message[..32] != multisig_account.key()[..]
+        || message[32..64] != proposal_account.key()[..]
+        || message[64] != ix_data.vote
+        || message[65] != ix_data.option
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let nonce = u64::from_le_bytes(
+        message[66..]
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?,
+    );
+
+    let (pda_nonce, nonce_bump) = VoteNonceState::find_pda(multisig_account.key(), &member);
+    if pda_nonce.ne(nonce_acc.key()) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    if nonce_acc.data_is_empty() {
+        let bump_bytes = [nonce_bump];
+        let signer_seeds = [
+            Seed::from(VoteNonceState::SEED.as_bytes()),
+            Seed::from(multisig_account.key()),
+            Seed::from(&member),
+            Seed::from(&bump_bytes[..]),
+        ];
+        create_pda_account::<VoteNonceState>(relayer, nonce_acc, &signer_seeds, &Rent::get()?)?;
+    }
+
+    let nonce_state = VoteNonceState::from_account_info(nonce_acc)?;
+    if nonce <= nonce_state.nonce {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    nonce_state.multisig = *multisig_account.key();
+    nonce_state.member = member;
+    nonce_state.nonce = nonce;
+    nonce_state.bump = nonce_bump;
+    nonce_state._padding = [0; 7];
+
+    Ok(member)
+}
+
+/// Re-evaluates the proposal after its tally changed: tracks when one of its options reached
+/// approval and fails it once the recorded no votes leave too few possible yes votes or reach
+/// the multisig's reject threshold. Neither happens before the multisig's quorum took part.
+/// Optimistic proposals are only ever failed by a veto.
+fn update_outcome(
+    proposal: &mut ProposalState,
+    multisig: &MultisigState,
+    members: &[u8],
+    votes: &[u8],
+    eligible_voters: u8,
+    now: u64,
+) -> ProgramResult {
+    let vetoed = multisig.vetoes(proposal.no_votes);
 
-        unsafe {
-            proposal_account.borrow_mut_data_unchecked()[..ProposalState::LEN]
-                .copy_from_slice(new_proposal_data.to_bytes().as_ref());
-            proposal_account.borrow_mut_data_unchecked()[ProposalState::LEN..]
-                .copy_from_slice(&new_votes);
+    if proposal.optimistic {
+        if vetoed {
+            proposal.status = ProposalStatus::Failed;
         }
+        return Ok(());
+    }
+
+    let yes_voters = &votes[..proposal.yes_votes as usize * 32];
+    let (admin_yes_votes, _) = multisig.count_votes_by_role(members, yes_voters)?;
+    proposal.admin_yes_votes = admin_yes_votes;
+
+    let quorate = proposal.reached_quorum(multisig.quorum);
+
+    // Plain proposals have a single option holding every yes vote
+    let mut approved = false;
+    for option in 0..proposal.options.max(1) {
+        let voters = proposal.option_voters(option);
+        let option_votes = voters.len() as u8;
+        let threshold = proposal.threshold(multisig, option);
+        let option_voters = &votes[voters.start * 32..voters.end * 32];
+        let (admin_votes, member_votes) = multisig.count_votes_by_role(members, option_voters)?;
+
+        approved |= option_votes >= threshold
+            && multisig.chambers_approve(admin_votes, member_votes)
+            && multisig.required_approvers_voted(proposal.tx_type, option_voters);
+    }
+    proposal.record_approval(quorate && approved, now);
+
+    let threshold = proposal.lowest_threshold(multisig);
+    if quorate && (vetoed || !proposal.can_reach_threshold(eligible_voters, threshold)) {
+        proposal.status = ProposalStatus::Failed;
     }
 
     Ok(())
@@ -42,6 +42,24 @@ pub fn process_instruction(
         MultisigInstructions::ExecuteTransaction => {
             instructions::process_execute_transaction_instruction(accounts, data)?
         }
+        MultisigInstructions::AcceptMembership => {
+            instructions::process_accept_membership_instruction(accounts, data)?
+        }
+        MultisigInstructions::LeaveMultisig => {
+            instructions::process_leave_multisig_instruction(accounts, data)?
+        }
+        MultisigInstructions::CancelProposal => {
+            instructions::process_cancel_proposal_instruction(accounts, data)?
+        }
+        MultisigInstructions::FinalizeExpired => {
+            instructions::process_finalize_expired_instruction(accounts, data)?
+        }
+        MultisigInstructions::DelegateVote => {
+            instructions::process_delegate_vote_instruction(accounts, data)?
+        }
+        MultisigInstructions::RegisterSession => {
+            instructions::process_register_session_instruction(accounts, data)?
+        }
         _ => todo!(),
     }
 
@@ -0,0 +1,22 @@
+use pinocchio::program_error::ProgramError;
+
+/// First byte of the vote authority accounts, which share a size and can't be told apart
+/// by their data otherwise
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccountType {
+    Delegation = 1,
+    Session = 2,
+}
+
+impl TryFrom<&u8> for AccountType {
+    type Error = ProgramError;
+
+    fn try_from(value: &u8) -> Result<Self, Self::Error> {
+        match *value {
+            1 => Ok(AccountType::Delegation),
+            2 => Ok(AccountType::Session),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
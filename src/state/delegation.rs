@@ -0,0 +1,90 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, sysvars::clock::Clock,
+};
+
+use crate::helper::account_init::StateDefinition;
+use crate::state::{multisig::TimeUnit, proposal::ProposalType};
+
+/// Lets `delegate` cast `delegator`'s vote, e.g. while the delegator is away
+#[derive(Pod, Zeroable, Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct DelegationState {
+    /// Always `AccountType::Delegation`
+    pub account_type: u8,
+    /// Bitmask of `ProposalType` discriminants the delegate may vote on
+    pub proposal_types: u8,
+    /// Bump seed for the delegation PDA
+    pub bump: u8,
+    /// The multisig's `TimeUnit` when the delegation was set
+    pub time_unit: u8,
+    pub _padding: [u8; 4],
+    pub multisig: Pubkey,
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+    /// Time, in `time_unit`, after which the delegate can no longer vote.
+    /// 0 never expires.
+    pub expiry: u64,
+}
+
+impl StateDefinition for DelegationState {
+    const LEN: usize = core::mem::size_of::<DelegationState>();
+    const SEED: &'static str = "delegation";
+}
+
+impl DelegationState {
+    pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
+        unsafe { &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self) }
+    }
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        if account_info.data_len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner() != &crate::ID {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(Self::from_account_info_unchecked(account_info))
+    }
+
+    pub fn find_pda(multisig: &Pubkey, delegator: &Pubkey) -> (Pubkey, u8) {
+        pinocchio::pubkey::find_program_address(
+            &[
+                Self::SEED.as_bytes(),
+                multisig.as_slice(),
+                delegator.as_slice(),
+            ],
+            &crate::ID,
+        )
+    }
+
+    pub fn validate_pda(&self, pda: &Pubkey) -> Result<(), ProgramError> {
+        let derived = pinocchio_pubkey::derive_address(
+            &[
+                Self::SEED.as_bytes(),
+                self.multisig.as_slice(),
+                self.delegator.as_slice(),
+            ],
+            Some(self.bump),
+            &crate::ID,
+        );
+        if derived != *pda {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(())
+    }
+
+    /// Whether `delegate` may vote for the delegator on a proposal of `tx_type` at the time
+    /// of `clock`
+    pub fn allows(
+        &self,
+        delegate: &Pubkey,
+        tx_type: ProposalType,
+        clock: &Clock,
+    ) -> Result<bool, ProgramError> {
+        let now = TimeUnit::try_from(&self.time_unit)?.now(clock);
+        Ok(self.delegate == *delegate
+            && self.proposal_types & (1 << tx_type as u8) != 0
+            && (self.expiry == 0 || now <= self.expiry))
+    }
+}
@@ -0,0 +1,71 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, sysvars::clock::Clock,
+};
+
+use crate::helper::account_init::StateDefinition;
+use crate::state::multisig::TimeUnit;
+
+/// Pending membership created by an approved add, waiting for the invitee's signature
+#[derive(Pod, Zeroable, Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct InvitationState {
+    /// Time, in `time_unit`, after which the invitation can no longer be accepted
+    pub expiry: u64,
+    pub multisig: Pubkey,
+    pub invitee: Pubkey,
+    /// 1 = admin, 0 = member
+    pub role: u8,
+    pub permissions: u8,
+    /// Bump seed for the invitation PDA
+    pub bump: u8,
+    /// The multisig's `TimeUnit` when the invitation was opened
+    pub time_unit: u8,
+    pub _padding: [u8; 4],
+}
+
+impl StateDefinition for InvitationState {
+    const LEN: usize = core::mem::size_of::<InvitationState>();
+    const SEED: &'static str = "invitation";
+}
+
+impl InvitationState {
+    pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
+        unsafe { &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self) }
+    }
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        if account_info.data_len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner() != &crate::ID {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(Self::from_account_info_unchecked(account_info))
+    }
+
+    pub fn find_pda(multisig: &Pubkey, invitee: &Pubkey) -> (Pubkey, u8) {
+        pinocchio::pubkey::find_program_address(
+            &[
+                Self::SEED.as_bytes(),
+                multisig.as_slice(),
+                invitee.as_slice(),
+            ],
+            &crate::ID,
+        )
+    }
+
+    /// Whether the invitation can no longer be accepted at the time of `clock`
+    pub fn expired(&self, clock: &Clock) -> Result<bool, ProgramError> {
+        Ok(TimeUnit::try_from(&self.time_unit)?.now(clock) > self.expiry)
+    }
+
+    /// Member record bytes in the layout `add_member` expects
+    pub fn member_data(&self) -> [u8; 34] {
+        let mut data = [0u8; 34];
+        data[..32].copy_from_slice(self.invitee.as_ref());
+        data[32] = self.role;
+        data[33] = self.permissions;
+        data
+    }
+}
@@ -15,14 +15,35 @@ pub enum MemberRole {
     Member = 0,
 }
 
+/// Actions a member may take, stored as bits of `MemberState::permissions`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Permission {
+    Initiate = 1 << 0,
+    Vote = 1 << 1,
+    Execute = 1 << 2,
+    Cancel = 1 << 3,
+    /// Create optimistic proposals, which pass after the challenge window unless vetoed
+    ProposeOptimistic = 1 << 4,
+}
+
 #[repr(C)]
 #[derive(Pod, Zeroable, Clone, Copy, Debug, PartialEq)]
 pub struct MemberState {
     pub pubkey: Pubkey,
+    /// Bitmask of `Permission` values
+    pub permissions: u8,
 }
 
 impl MemberState {
-    pub const LEN: usize = 32;
+    pub const LEN: usize = 33;
+
+    pub const ALL_PERMISSIONS: u8 = Permission::Initiate as u8
+        | Permission::Vote as u8
+        | Permission::Execute as u8
+        | Permission::Cancel as u8
+        | Permission::ProposeOptimistic as u8;
+    pub const DEFAULT_MEMBER_PERMISSIONS: u8 = Permission::Vote as u8 | Permission::Execute as u8;
 
     #[inline]
     pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
@@ -39,18 +60,82 @@ impl MemberState {
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProgramError> {
-        if bytes.len() < 32 {
+        if bytes.len() < Self::LEN {
             return Err(ProgramError::InvalidAccountData);
         }
         let pubkey_bytes = unsafe { *(bytes.as_ptr() as *const [u8; 32]) };
         Ok(MemberState {
             pubkey: Pubkey::from(pubkey_bytes),
+            permissions: bytes[32],
         })
     }
 
     pub fn to_bytes(&self) -> Result<[u8; Self::LEN], ProgramError> {
         let mut bytes = [0u8; Self::LEN];
-        bytes.copy_from_slice(&self.pubkey.as_ref());
+        bytes[..32].copy_from_slice(&self.pubkey.as_ref());
+        bytes[32] = self.permissions;
         Ok(bytes)
     }
+
+    /// Default permission mask for a member added with the given role
+    pub fn default_permissions(role: u8) -> u8 {
+        if role == MemberRole::Admin as u8 {
+            Self::ALL_PERMISSIONS
+        } else {
+            Self::DEFAULT_MEMBER_PERMISSIONS
+        }
+    }
+
+    /// Parses a member record of an `UpdateMember` add: the pubkey, the role (1 = admin,
+    /// 0 = member) and a permission mask, where 0 falls back to the role's defaults
+    pub fn parse_record(data: &[u8]) -> Result<(Pubkey, u8, u8), ProgramError> {
+        if data.len() < 34 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut pk_bytes = [0u8; 32];
+        pk_bytes.copy_from_slice(&data[..32]);
+        let role = data[32];
+
+        let permissions = match data[33] {
+            0 => Self::default_permissions(role),
+            mask => mask,
+        };
+        if permissions & !Self::ALL_PERMISSIONS != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok((Pubkey::from(pk_bytes), role, permissions))
+    }
+
+    #[inline]
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions & permission as u8 != 0
+    }
+
+    /// Looks up `pubkey` in the member section of a multisig account
+    pub fn find(
+        member_data: &[u8],
+        pubkey: &Pubkey,
+    ) -> Result<Option<(usize, Self)>, ProgramError> {
+        for (idx, chunk) in member_data.chunks_exact(Self::LEN).enumerate() {
+            let member = Self::from_bytes(chunk)?;
+            if member.pubkey == *pubkey {
+                return Ok(Some((idx, member)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Counts the members in `member_data` holding `permission`
+    pub fn count_with_permission(
+        member_data: &[u8],
+        permission: Permission,
+    ) -> Result<u8, ProgramError> {
+        let mut count = 0u8;
+        for chunk in member_data.chunks_exact(Self::LEN) {
+            count += Self::from_bytes(chunk)?.has_permission(permission) as u8;
+        }
+        Ok(count)
+    }
 }
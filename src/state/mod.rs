@@ -1,9 +1,19 @@
+pub mod account_type;
+pub mod delegation;
+pub mod invitation;
 pub mod member;
 pub mod multisig;
 pub mod proposal;
+pub mod session;
 pub mod transaction;
+pub mod vote_nonce;
 
+pub use account_type::*;
+pub use delegation::*;
+pub use invitation::*;
 pub use member::*;
 pub use multisig::*;
 pub use proposal::*;
+pub use session::*;
 pub use transaction::*;
+pub use vote_nonce::*;
@@ -3,6 +3,7 @@ use pinocchio::{
     account_info::{AccountInfo, Ref},
     program_error::ProgramError,
     pubkey::Pubkey,
+    sysvars::clock::Clock,
     ProgramResult,
 };
 
@@ -10,6 +11,8 @@ use bytemuck::{Pod, Zeroable};
 
 use crate::helper::account_init::StateDefinition;
 use crate::instructions::init_multisig::InitMultisigIxData;
+use crate::state::member::{MemberState, Permission};
+use crate::state::proposal::ProposalType;
 
 #[derive(Pod, Zeroable, Debug, Clone, Copy, PartialEq)]
 #[repr(C)]
@@ -17,15 +20,35 @@ pub struct MultisigState {
     pub seed: u64,
     /// spending limit
     pub spending_limit: u64,
-    /// Maximum expiry time for proposals
+    /// Maximum time a proposal stays open for voting, in the multisig's `TimeUnit`
     pub max_expiry: u64,
     /// The index of the last transaction executed
     pub transaction_index: u64,
     // Last stale transaction index. All transactions up until this index are stale.
     pub stale_transaction_index: u64,
+    /// How long an invitation stays open, in the multisig's `TimeUnit`. 0 adds approved
+    /// members directly without asking them to accept.
+    pub invitation_ttl: u64,
+    /// How long an approved proposal must wait before it can be executed, in the multisig's `TimeUnit`
+    pub time_lock: u64,
+    /// Lamports the treasury pays whoever finalizes an expired proposal, 0 for none
+    pub finalize_reward: u64,
+    /// How long an optimistic proposal stays open to a veto before it can be executed, in the
+    /// multisig's `TimeUnit`. 0 disables optimistic proposals.
+    pub challenge_window: u64,
+    /// Exclusive lamport upper bounds of the transfer tiers, ascending
+    pub transfer_tier_limits: [u64; MultisigState::MAX_TRANSFER_TIERS],
     pub primary_seed: u16,
+    /// Optimistic proposals not yet executed, cancelled or failed
+    pub open_optimistic: u16,
+    /// Proposals of any kind not yet executed, cancelled or failed
+    pub open_proposals: u16,
     /// Treasury account for the multisig, optional
     pub treasury: Pubkey,
+    /// Receives rent freed when multisig-owned accounts shrink or close, the treasury by default
+    pub rent_collector: Pubkey,
+    /// Members whose yes vote every proposal of the types in `required_approver_types` needs
+    pub required_approvers: [Pubkey; MultisigState::MAX_REQUIRED_APPROVERS],
     /// Bump seed for the treasury PDA
     pub treasury_bump: u8,
     /// Bump seed for the multisig PDA
@@ -34,7 +57,30 @@ pub struct MultisigState {
     pub min_threshold: u8,
     pub num_members: u8,
     pub admin_counter: u8,
-    pub _padding: [u8; 1],
+    /// How `min_threshold` reacts when member updates shrink the member set, see `RemovalPolicy`
+    pub removal_policy: u8,
+    /// Whether deadlines and durations are unix timestamps or slots, see `TimeUnit`
+    pub time_unit: u8,
+    /// Number of no votes that fails a proposal outright, 0 to disable
+    pub reject_threshold: u8,
+    /// Yes votes needed per `ProposalType`, indexed by its discriminant. 0 falls back to
+    /// the base threshold, see `base_threshold`.
+    pub type_thresholds: [u8; 3],
+    /// Yes votes needed from admins on top of the overall threshold, 0 for none
+    pub admin_threshold: u8,
+    /// Yes votes needed from regular members on top of the overall threshold, 0 for none
+    pub member_threshold: u8,
+    /// Yes votes a system transfer below the matching `transfer_tier_limits` entry needs,
+    /// 0 for unused tiers
+    pub transfer_tier_thresholds: [u8; MultisigState::MAX_TRANSFER_TIERS],
+    /// Bitmask of `ProposalType` discriminants per `required_approvers` entry, 0 for unused slots
+    pub required_approver_types: [u8; MultisigState::MAX_REQUIRED_APPROVERS],
+    /// Members who must vote, yes, no or abstain, before a proposal can be decided, 0 for none
+    pub quorum: u8,
+    /// Base threshold as a percentage of `num_members`, rounded up, in place of
+    /// `min_threshold`. 0 uses `min_threshold`.
+    pub threshold_percent: u8,
+    pub _padding: [u8; 3],
 }
 
 impl StateDefinition for MultisigState {
@@ -43,6 +89,15 @@ impl StateDefinition for MultisigState {
 }
 
 impl MultisigState {
+    /// Voting period used when a proposal doesn't ask for one, capped by `max_expiry`
+    pub const DEFAULT_PROPOSAL_EXPIRY: u64 = 7 * 24 * 60 * 60;
+    /// `DEFAULT_PROPOSAL_EXPIRY` in 400ms slots
+    pub const DEFAULT_PROPOSAL_EXPIRY_SLOTS: u64 = Self::DEFAULT_PROPOSAL_EXPIRY * 5 / 2;
+    pub const MAX_TRANSFER_TIERS: usize = 4;
+    pub const MAX_REQUIRED_APPROVERS: usize = 4;
+    /// One bit per `ProposalType` discriminant
+    pub const ALL_PROPOSAL_TYPES: u8 = 0b111;
+
     pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
         unsafe { &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self) }
     }
@@ -56,6 +111,19 @@ impl MultisigState {
         Ok(Self::from_account_info_unchecked(account_info))
     }
 
+    /// Checks `pda` is the multisig derived from this state's `primary_seed` and `bump`
+    pub fn validate_pda(&self, pda: &Pubkey, bump: u8) -> Result<(), ProgramError> {
+        let derived = pinocchio_pubkey::derive_address(
+            &[Self::SEED.as_bytes(), &self.primary_seed.to_le_bytes()],
+            Some(bump),
+            &crate::ID,
+        );
+        if derived != *pda {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(())
+    }
+
     pub fn new(
         &mut self,
         treasury: &Pubkey,
@@ -65,22 +133,468 @@ impl MultisigState {
     ) {
         self.spending_limit = 0;
         self.treasury = *treasury;
+        self.rent_collector = *treasury;
         self.treasury_bump = treasury_bump;
         self.bump = multisig_bump;
         self.min_threshold = ix_data.min_threshold;
         self.max_expiry = ix_data.max_expiry;
         self.transaction_index = 0;
         self.stale_transaction_index = 0;
+        self.invitation_ttl = 0;
+        self.time_lock = 0;
+        self.finalize_reward = 0;
+        self.challenge_window = 0;
         self.num_members = 0;
         self.admin_counter = 0;
         self.primary_seed = ix_data.primary_seed;
-        self._padding = [0; 1];
+        self.open_optimistic = 0;
+        self.open_proposals = 0;
+        self.removal_policy = RemovalPolicy::Reject as u8;
+        self.time_unit = TimeUnit::UnixTimestamp as u8;
+        self.reject_threshold = 0;
+        self.type_thresholds = [0; 3];
+        self.admin_threshold = 0;
+        self.member_threshold = 0;
+        self.transfer_tier_limits = [0; Self::MAX_TRANSFER_TIERS];
+        self.transfer_tier_thresholds = [0; Self::MAX_TRANSFER_TIERS];
+        self.required_approvers = [Pubkey::default(); Self::MAX_REQUIRED_APPROVERS];
+        self.required_approver_types = [0; Self::MAX_REQUIRED_APPROVERS];
+        self.quorum = 0;
+        self.threshold_percent = 0;
+        self._padding = [0; 3];
+    }
+
+    /// Rejects any configuration that would leave the multisig unusable or trivially
+    /// takeover-able. `member_data` is the member section following the header.
+    pub fn check_invariants(&self, member_data: &[u8]) -> ProgramResult {
+        let members_len = self.num_members as usize * MemberState::LEN;
+        if member_data.len() < members_len {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // At least one admin, and the admin section fits in the member list
+        if self.admin_counter == 0 || self.admin_counter > self.num_members {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Proposals would expire as soon as they are created, or before their timelock ends
+        if self.max_expiry == 0 || self.time_lock >= self.max_expiry {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // A zero threshold lets anyone execute, one above the member count locks the multisig
+        if self.min_threshold == 0 || self.min_threshold > self.num_members {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if self.threshold_percent > 100 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if self
+            .type_thresholds
+            .iter()
+            .chain(self.transfer_tier_thresholds.iter())
+            .any(|&t| t > self.num_members)
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let members = &member_data[..members_len];
+        let mut voters = 0u8;
+        let mut admin_voters = 0u8;
+        let mut initiators = 0u8;
+        let mut executors = 0u8;
+
+        for (idx, chunk) in members.chunks_exact(MemberState::LEN).enumerate() {
+            let member = MemberState::from_bytes(chunk)?;
+
+            if member.permissions & !MemberState::ALL_PERMISSIONS != 0 {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            // Duplicate keys would let one signer count more than once
+            let rest = &members[(idx + 1) * MemberState::LEN..];
+            if MemberState::find(rest, &member.pubkey)?.is_some() {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            voters += member.has_permission(Permission::Vote) as u8;
+            admin_voters += (idx < self.admin_counter as usize
+                && member.has_permission(Permission::Vote)) as u8;
+            initiators += member.has_permission(Permission::Initiate) as u8;
+            executors += member.has_permission(Permission::Execute) as u8;
+        }
+
+        let highest_threshold = self
+            .type_thresholds
+            .iter()
+            .chain(self.transfer_tier_thresholds.iter())
+            .fold(self.base_threshold(), |highest, &t| highest.max(t));
+
+        // A reject threshold no vote can reach would leave optimistic proposals unchecked
+        if voters
+            < highest_threshold
+                .max(self.quorum)
+                .max(self.reject_threshold)
+            || initiators == 0
+            || executors == 0
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // A required approver who can't vote would block their proposal types for good
+        for (approver, &types) in self
+            .required_approvers
+            .iter()
+            .zip(self.required_approver_types.iter())
+        {
+            if types & !Self::ALL_PROPOSAL_TYPES != 0 {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if types == 0 {
+                continue;
+            }
+            match MemberState::find(members, approver)? {
+                Some((_, member)) if member.has_permission(Permission::Vote) => {}
+                _ => return Err(ProgramError::InvalidAccountData),
+            }
+        }
+
+        // Each chamber needs enough voters of its own
+        if admin_voters < self.admin_threshold || voters - admin_voters < self.member_threshold {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(())
+    }
+
+    /// Current time from `clock` in the multisig's `TimeUnit`
+    pub fn current_time(&self, clock: &Clock) -> Result<u64, ProgramError> {
+        Ok(TimeUnit::try_from(&self.time_unit)?.now(clock))
+    }
+
+    /// Turns a requested voting period into an absolute deadline from `now`. 0 picks the
+    /// default period, and anything longer than `max_expiry` is cut down to it.
+    pub fn proposal_deadline(&self, requested_expiry: u64, now: u64) -> Result<u64, ProgramError> {
+        let duration = match (requested_expiry, TimeUnit::try_from(&self.time_unit)?) {
+            (0, TimeUnit::UnixTimestamp) => Self::DEFAULT_PROPOSAL_EXPIRY,
+            (0, TimeUnit::Slot) => Self::DEFAULT_PROPOSAL_EXPIRY_SLOTS,
+            (duration, _) => duration,
+        };
+
+        now.checked_add(duration.min(self.max_expiry))
+            .ok_or(ProgramError::ArithmeticOverflow)
     }
 
     pub fn update_threshold(&mut self, threshold: u8) {
         self.min_threshold = threshold;
     }
 
+    pub fn update_threshold_percent(&mut self, threshold_percent: u8) {
+        self.threshold_percent = threshold_percent;
+    }
+
+    /// Yes votes a proposal needs when its type has no threshold of its own: `min_threshold`,
+    /// or `threshold_percent` of the current member count, rounded up and at least 1
+    pub fn base_threshold(&self) -> u8 {
+        if self.threshold_percent == 0 {
+            return self.min_threshold;
+        }
+
+        let scaled = self.threshold_percent as u16 * self.num_members as u16;
+        (scaled.div_ceil(100) as u8).max(1)
+    }
+
+    /// Yes votes a proposal of `tx_type` needs to pass
+    pub fn threshold_for(&self, tx_type: ProposalType) -> u8 {
+        match self.type_thresholds[tx_type as usize] {
+            0 => self.base_threshold(),
+            threshold => threshold,
+        }
+    }
+
+    pub fn update_chamber_thresholds(&mut self, admin_threshold: u8, member_threshold: u8) {
+        self.admin_threshold = admin_threshold;
+        self.member_threshold = member_threshold;
+    }
+
+    /// Splits the 32-byte keys in `voters` into (admin, regular member) counts. Keys that
+    /// are no longer members count for neither.
+    pub fn count_votes_by_role(
+        &self,
+        member_data: &[u8],
+        voters: &[u8],
+    ) -> Result<(u8, u8), ProgramError> {
+        let members_len = self.num_members as usize * MemberState::LEN;
+        if member_data.len() < members_len {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut admin_votes = 0u8;
+        let mut member_votes = 0u8;
+
+        for voter in voters.chunks_exact(32) {
+            let voter: Pubkey = voter
+                .try_into()
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+
+            match MemberState::find(&member_data[..members_len], &voter)? {
+                Some((idx, _)) if idx < self.admin_counter as usize => admin_votes += 1,
+                Some(_) => member_votes += 1,
+                None => {}
+            }
+        }
+
+        Ok((admin_votes, member_votes))
+    }
+
+    /// Whether both chambers reached their own threshold
+    pub fn chambers_approve(&self, admin_votes: u8, member_votes: u8) -> bool {
+        admin_votes >= self.admin_threshold && member_votes >= self.member_threshold
+    }
+
+    /// Yes votes a CPI moving `amount` lamports needs: the threshold of the first tier whose
+    /// limit is above `amount`, or past every tier the strictest of them and the CPI threshold.
+    /// Without tiers this is the CPI threshold.
+    pub fn threshold_for_transfer(&self, amount: u64) -> u8 {
+        let mut strictest = self.threshold_for(ProposalType::Cpi);
+
+        for (&limit, &threshold) in self
+            .transfer_tier_limits
+            .iter()
+            .zip(self.transfer_tier_thresholds.iter())
+        {
+            if threshold == 0 {
+                continue;
+            }
+            if amount < limit {
+                return threshold;
+            }
+            strictest = strictest.max(threshold);
+        }
+
+        strictest
+    }
+
+    /// Whether any transfer tier is set, making a transfer's threshold depend on its amount
+    pub fn has_transfer_tiers(&self) -> bool {
+        self.transfer_tier_thresholds
+            .iter()
+            .any(|&threshold| threshold != 0)
+    }
+
+    /// Sets the threshold for transfers below `limit` lamports, keeping the tiers sorted by
+    /// limit. A threshold of 0 removes the tier.
+    pub fn update_transfer_tier(&mut self, limit: u64, threshold: u8) -> ProgramResult {
+        let mut tiers = [(0u64, 0u8); Self::MAX_TRANSFER_TIERS];
+        let mut count = 0;
+
+        for idx in 0..Self::MAX_TRANSFER_TIERS {
+            let tier = (
+                self.transfer_tier_limits[idx],
+                self.transfer_tier_thresholds[idx],
+            );
+            if tier.1 > 0 && tier.0 != limit {
+                tiers[count] = tier;
+                count += 1;
+            }
+        }
+
+        if threshold > 0 {
+            if count == Self::MAX_TRANSFER_TIERS {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            tiers[count] = (limit, threshold);
+            count += 1;
+        }
+
+        tiers[..count].sort_unstable_by_key(|&(limit, _)| limit);
+
+        for (idx, (limit, threshold)) in tiers.into_iter().enumerate() {
+            self.transfer_tier_limits[idx] = limit;
+            self.transfer_tier_thresholds[idx] = threshold;
+        }
+
+        Ok(())
+    }
+
+    /// Whether every required approver for `tx_type` is among `yes_voters`
+    pub fn required_approvers_voted(&self, tx_type: ProposalType, yes_voters: &[u8]) -> bool {
+        let type_bit = 1 << tx_type as u8;
+
+        self.required_approvers
+            .iter()
+            .zip(self.required_approver_types.iter())
+            .filter(|(_, &types)| types & type_bit != 0)
+            .all(|(approver, _)| {
+                yes_voters
+                    .chunks_exact(32)
+                    .any(|voter| voter == approver.as_ref())
+            })
+    }
+
+    /// Makes `approver`'s yes vote mandatory for the proposal types in the `types` bitmask,
+    /// 0 drops them from the required approvers.
+    pub fn update_required_approver(&mut self, approver: &Pubkey, types: u8) -> ProgramResult {
+        if types & !Self::ALL_PROPOSAL_TYPES != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let slot = self
+            .required_approvers
+            .iter()
+            .zip(self.required_approver_types.iter())
+            .position(|(key, &t)| t != 0 && key == approver)
+            .or_else(|| self.required_approver_types.iter().position(|&t| t == 0));
+
+        match slot {
+            Some(idx) => {
+                self.required_approvers[idx] = if types == 0 {
+                    Pubkey::default()
+                } else {
+                    *approver
+                };
+                self.required_approver_types[idx] = types;
+            }
+            // Dropping someone who isn't required is a no-op
+            None if types == 0 => {}
+            None => return Err(ProgramError::InvalidInstructionData),
+        }
+
+        Ok(())
+    }
+
+    /// 0 makes `tx_type` use the base threshold again
+    pub fn update_type_threshold(&mut self, tx_type: ProposalType, threshold: u8) {
+        self.type_thresholds[tx_type as usize] = threshold;
+    }
+
+    /// Open optimistic proposals rely on the reject threshold as their only check, so it
+    /// can't be turned off until they are closed
+    pub fn update_reject_threshold(&mut self, reject_threshold: u8) -> ProgramResult {
+        if reject_threshold == 0 && self.open_optimistic > 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        self.reject_threshold = reject_threshold;
+        Ok(())
+    }
+
+    pub fn update_invitation_ttl(&mut self, invitation_ttl: u64) {
+        self.invitation_ttl = invitation_ttl;
+    }
+
+    pub fn update_time_lock(&mut self, time_lock: u64) {
+        self.time_lock = time_lock;
+    }
+
+    pub fn update_finalize_reward(&mut self, finalize_reward: u64) {
+        self.finalize_reward = finalize_reward;
+    }
+
+    /// Proposal deadlines and the durations added to them share the multisig's unit, so it
+    /// can only change while no proposal is open, and the stored durations are converted to
+    /// the new unit. Invitations, delegations and sessions record the unit their expiry was
+    /// set in.
+    pub fn update_time_unit(&mut self, time_unit: TimeUnit) -> ProgramResult {
+        if self.open_proposals > 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let current = TimeUnit::try_from(&self.time_unit)?;
+        for duration in [
+            &mut self.max_expiry,
+            &mut self.invitation_ttl,
+            &mut self.time_lock,
+            &mut self.challenge_window,
+        ] {
+            *duration = current.convert(*duration, time_unit)?;
+        }
+
+        self.time_unit = time_unit as u8;
+        Ok(())
+    }
+
+    pub fn update_challenge_window(&mut self, challenge_window: u64) {
+        self.challenge_window = challenge_window;
+    }
+
+    /// Counts a newly created proposal towards the open ones
+    pub fn open_proposal(&mut self, optimistic: bool) -> ProgramResult {
+        self.open_proposals = self
+            .open_proposals
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if optimistic {
+            self.open_optimistic = self
+                .open_optimistic
+                .checked_add(1)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// Stops counting a proposal once it was executed, cancelled or failed
+    pub fn close_proposal(&mut self, optimistic: bool) {
+        self.open_proposals = self.open_proposals.saturating_sub(1);
+        if optimistic {
+            self.open_optimistic = self.open_optimistic.saturating_sub(1);
+        }
+    }
+
+    /// Whether `no_votes` reach the reject threshold, when one is set
+    pub fn vetoes(&self, no_votes: u8) -> bool {
+        self.reject_threshold > 0 && no_votes >= self.reject_threshold
+    }
+
+    pub fn update_quorum(&mut self, quorum: u8) {
+        self.quorum = quorum;
+    }
+
+    pub fn update_rent_collector(&mut self, rent_collector: &Pubkey) {
+        self.rent_collector = *rent_collector;
+    }
+
+    pub fn update_removal_policy(&mut self, removal_policy: RemovalPolicy) {
+        self.removal_policy = removal_policy as u8;
+    }
+
+    /// Applies the removal policy after the member set shrank from `previous_members`
+    /// (with `previous_threshold`) to the current `num_members`.
+    pub fn apply_removal_policy(
+        &mut self,
+        previous_members: u8,
+        previous_threshold: u8,
+    ) -> ProgramResult {
+        if self.num_members >= previous_members {
+            return Ok(());
+        }
+
+        let policy = RemovalPolicy::try_from(&self.removal_policy)?;
+        self.min_threshold =
+            policy.adjust(previous_threshold, previous_members, self.num_members)?;
+
+        // Per-type and transfer tier thresholds, the quorum and the reject threshold only change
+        // through update_multisig, so their current value is also the one from before the
+        // removal
+        for threshold in self
+            .type_thresholds
+            .iter_mut()
+            .chain(self.transfer_tier_thresholds.iter_mut())
+        {
+            if *threshold > 0 {
+                *threshold = policy.adjust(*threshold, previous_members, self.num_members)?;
+            }
+        }
+        if self.quorum > 0 {
+            self.quorum = policy.adjust(self.quorum, previous_members, self.num_members)?;
+        }
+        if self.reject_threshold > 0 {
+            self.reject_threshold =
+                policy.adjust(self.reject_threshold, previous_members, self.num_members)?;
+        }
+
+        Ok(())
+    }
+
     pub fn update_spending_limit(&mut self, spending_limit: u64) {
         self.spending_limit = spending_limit;
     }
@@ -93,3 +607,99 @@ impl MultisigState {
         self.transaction_index += 1;
     }
 }
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RemovalPolicy {
+    /// Refuse member removals that would push the member count below the threshold
+    Reject = 0,
+    /// Lower the threshold to the remaining member count when needed
+    Clamp = 1,
+    /// Scale the threshold with the member count, rounding up (e.g. 2 of 3 becomes 2 of 2)
+    KeepRatio = 2,
+}
+
+impl RemovalPolicy {
+    /// Value of `threshold` once the member set shrank from `previous_members` to `num_members`
+    fn adjust(
+        self,
+        threshold: u8,
+        previous_members: u8,
+        num_members: u8,
+    ) -> Result<u8, ProgramError> {
+        match self {
+            RemovalPolicy::Reject => {
+                if threshold > num_members {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                Ok(threshold)
+            }
+            RemovalPolicy::Clamp => Ok(threshold.min(num_members)),
+            RemovalPolicy::KeepRatio => {
+                // ceil(threshold * num_members / previous_members), at least 1
+                let scaled = threshold as u16 * num_members as u16;
+                let threshold = scaled.div_ceil(previous_members as u16);
+                Ok((threshold as u8).max(1))
+            }
+        }
+    }
+}
+
+impl TryFrom<&u8> for RemovalPolicy {
+    type Error = ProgramError;
+
+    fn try_from(value: &u8) -> Result<Self, Self::Error> {
+        match *value {
+            0 => Ok(RemovalPolicy::Reject),
+            1 => Ok(RemovalPolicy::Clamp),
+            2 => Ok(RemovalPolicy::KeepRatio),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeUnit {
+    /// `Clock::unix_timestamp`, in seconds
+    UnixTimestamp = 0,
+    /// `Clock::slot`
+    Slot = 1,
+}
+
+impl TimeUnit {
+    /// Current time from `clock` in this unit
+    pub fn now(self, clock: &Clock) -> u64 {
+        match self {
+            TimeUnit::UnixTimestamp => clock.unix_timestamp as u64,
+            TimeUnit::Slot => clock.slot,
+        }
+    }
+
+    /// `duration` in this unit expressed in `to`, counting 400ms slots and rounding up so
+    /// a converted duration is never shorter
+    pub fn convert(self, duration: u64, to: TimeUnit) -> Result<u64, ProgramError> {
+        let (numerator, denominator) = match (self, to) {
+            (TimeUnit::UnixTimestamp, TimeUnit::Slot) => (5, 2),
+            (TimeUnit::Slot, TimeUnit::UnixTimestamp) => (2, 5),
+            _ => return Ok(duration),
+        };
+
+        duration
+            .checked_mul(numerator)
+            .map(|scaled| scaled.div_ceil(denominator))
+            .ok_or(ProgramError::ArithmeticOverflow)
+    }
+}
+
+impl TryFrom<&u8> for TimeUnit {
+    type Error = ProgramError;
+
+    fn try_from(value: &u8) -> Result<Self, Self::Error> {
+        match *value {
+            0 => Ok(TimeUnit::UnixTimestamp),
+            1 => Ok(TimeUnit::Slot),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
@@ -1,18 +1,29 @@
 use crate::helper::account_init::StateDefinition;
+use crate::state::multisig::MultisigState;
+use core::ops::Range;
 use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(C)]
 pub struct ProposalState {
-    pub proposal_id: u16, // Unique identifier for the proposal
-    pub expiry: u64,      // Adjust size as needed is it needed here?
+    pub expiry: u64, // Deadline for voting and execution, set from the multisig's max_expiry
     pub created_time: u64,
+    pub approved_time: u64, // When yes votes last reached the threshold, 0 while below it
+    pub creator: Pubkey,    // Gets the vote storage rent back when the proposal is finalized
+    pub proposal_id: u16,   // Unique identifier for the proposal
     pub status: ProposalStatus,
     pub tx_type: ProposalType,
-    pub bump: u8,          // Bump seed for PDA
-    pub yes_votes: u8,     // Number of yes votes
-    pub no_votes: u8,      // Number of no votes
-    pub _padding: [u8; 3], // padding to reach multiple of 8
+    pub bump: u8,                                            // Bump seed for PDA
+    pub yes_votes: u8,                                       // Number of yes votes
+    pub no_votes: u8,                                        // Number of no votes
+    pub admin_yes_votes: u8, // Yes votes cast by admins, as of the last vote
+    pub abstain_votes: u8,   // Counts towards quorum only
+    pub optimistic: bool,    // Passes after the challenge window unless vetoed, yes votes unused
+    pub options: u8,         // Number of candidate transactions, 0 for an unbound yes/no proposal
+    pub option_votes: [u8; ProposalState::MAX_OPTIONS], // Yes votes per option
+    pub delegations: u8,     // (delegator, delegate) key pairs stored after the vote keys
+    pub candidates: [Pubkey; ProposalState::MAX_OPTIONS], // Transactions members choose between
+    pub transfer_amounts: [u64; ProposalState::MAX_OPTIONS], // Lamports each candidate transfers
 }
 
 impl StateDefinition for ProposalState {
@@ -21,6 +32,12 @@ impl StateDefinition for ProposalState {
 }
 
 impl ProposalState {
+    /// Most candidate transactions a multi-choice proposal can hold
+    pub const MAX_OPTIONS: usize = 4;
+    /// `transfer_amounts` entry of a candidate that isn't a system transfer, or of an option
+    /// with no candidate
+    pub const NO_TRANSFER: u64 = u64::MAX;
+
     pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
         unsafe { &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self) }
     }
@@ -57,37 +74,340 @@ impl ProposalState {
             return Err(ProgramError::InvalidAccountData);
         }
         Ok(Self {
-            proposal_id: u16::from_le_bytes([bytes[0], bytes[1]]),
             expiry: u64::from_le_bytes([
-                bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7], bytes[8], bytes[9],
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
             ]),
             created_time: u64::from_le_bytes([
-                bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15], bytes[16],
-                bytes[17],
+                bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14],
+                bytes[15],
+            ]),
+            approved_time: u64::from_le_bytes([
+                bytes[16], bytes[17], bytes[18], bytes[19], bytes[20], bytes[21], bytes[22],
+                bytes[23],
             ]),
-            status: ProposalStatus::try_from(&bytes[18])?,
-            tx_type: ProposalType::try_from(&bytes[19])?,
-            bump: bytes[20],
-            yes_votes: bytes[21],
-            no_votes: bytes[22],
-            _padding: [0; 3],
+            creator: bytes[24..56]
+                .try_into()
+                .map_err(|_| ProgramError::InvalidAccountData)?,
+            proposal_id: u16::from_le_bytes([bytes[56], bytes[57]]),
+            status: ProposalStatus::try_from(&bytes[58])?,
+            tx_type: ProposalType::try_from(&bytes[59])?,
+            bump: bytes[60],
+            yes_votes: bytes[61],
+            no_votes: bytes[62],
+            admin_yes_votes: bytes[63],
+            abstain_votes: bytes[64],
+            optimistic: bytes[65] != 0,
+            options: bytes[66],
+            option_votes: [bytes[67], bytes[68], bytes[69], bytes[70]],
+            delegations: bytes[71],
+            candidates: [
+                bytes[72..104]
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidAccountData)?,
+                bytes[104..136]
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidAccountData)?,
+                bytes[136..168]
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidAccountData)?,
+                bytes[168..200]
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidAccountData)?,
+            ],
+            transfer_amounts: [
+                u64::from_le_bytes(
+                    bytes[200..208]
+                        .try_into()
+                        .map_err(|_| ProgramError::InvalidAccountData)?,
+                ),
+                u64::from_le_bytes(
+                    bytes[208..216]
+                        .try_into()
+                        .map_err(|_| ProgramError::InvalidAccountData)?,
+                ),
+                u64::from_le_bytes(
+                    bytes[216..224]
+                        .try_into()
+                        .map_err(|_| ProgramError::InvalidAccountData)?,
+                ),
+                u64::from_le_bytes(
+                    bytes[224..232]
+                        .try_into()
+                        .map_err(|_| ProgramError::InvalidAccountData)?,
+                ),
+            ],
         })
     }
 
     pub fn to_bytes(&self) -> [u8; Self::LEN] {
         let mut bytes = [0u8; Self::LEN];
-        bytes[0..2].copy_from_slice(&self.proposal_id.to_le_bytes());
-        bytes[2..10].copy_from_slice(&self.expiry.to_le_bytes());
-        bytes[10..18].copy_from_slice(&self.created_time.to_le_bytes());
-        bytes[18] = self.status as u8;
-        bytes[19] = self.tx_type as u8;
-        bytes[20] = self.bump;
-        bytes[21] = self.yes_votes;
-        bytes[22] = self.no_votes;
-        bytes[23..26].copy_from_slice(&self._padding);
+        bytes[0..8].copy_from_slice(&self.expiry.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.created_time.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.approved_time.to_le_bytes());
+        bytes[24..56].copy_from_slice(self.creator.as_ref());
+        bytes[56..58].copy_from_slice(&self.proposal_id.to_le_bytes());
+        bytes[58] = self.status as u8;
+        bytes[59] = self.tx_type as u8;
+        bytes[60] = self.bump;
+        bytes[61] = self.yes_votes;
+        bytes[62] = self.no_votes;
+        bytes[63] = self.admin_yes_votes;
+        bytes[64] = self.abstain_votes;
+        bytes[65] = self.optimistic as u8;
+        bytes[66] = self.options;
+        bytes[67..71].copy_from_slice(&self.option_votes);
+        bytes[71] = self.delegations;
+        for (idx, candidate) in self.candidates.iter().enumerate() {
+            bytes[72 + idx * 32..104 + idx * 32].copy_from_slice(candidate.as_ref());
+        }
+        for (idx, amount) in self.transfer_amounts.iter().enumerate() {
+            bytes[200 + idx * 8..208 + idx * 8].copy_from_slice(&amount.to_le_bytes());
+        }
         bytes
     }
 
+    /// Lamports the candidate for `option` transfers, `None` when it isn't a system transfer
+    /// or the proposal isn't bound to a transaction
+    pub fn transfer_amount(&self, option: u8) -> Option<u64> {
+        match self.transfer_amounts.get(option as usize) {
+            Some(&amount) if amount != Self::NO_TRANSFER => Some(amount),
+            _ => None,
+        }
+    }
+
+    /// Yes votes `option` needs to pass. Votes, finalization and execution all go through
+    /// this so a transfer is held to its tier on every path.
+    pub fn threshold(&self, multisig: &MultisigState, option: u8) -> u8 {
+        match (self.tx_type, self.transfer_amount(option)) {
+            (ProposalType::Cpi, Some(amount)) => multisig.threshold_for_transfer(amount),
+            (tx_type, _) => multisig.threshold_for(tx_type),
+        }
+    }
+
+    /// Threshold of the option that is easiest to pass, which decides whether the proposal
+    /// can still succeed at all
+    pub fn lowest_threshold(&self, multisig: &MultisigState) -> u8 {
+        (0..self.options.max(1))
+            .map(|option| self.threshold(multisig, option))
+            .min()
+            .unwrap_or(u8::MAX)
+    }
+
+    /// Whether enough members could still vote yes to reach `threshold`, counting
+    /// every recorded no vote as final. `eligible_voters` is the number of members
+    /// holding the `Vote` permission.
+    pub fn can_reach_threshold(&self, eligible_voters: u8, threshold: u8) -> bool {
+        eligible_voters.saturating_sub(self.no_votes) >= threshold
+    }
+
+    /// Members who voted so far, whatever their vote
+    pub fn participation(&self) -> u8 {
+        self.yes_votes + self.no_votes + self.abstain_votes
+    }
+
+    /// Whether enough members took part for the votes to decide the proposal
+    pub fn reached_quorum(&self, quorum: u8) -> bool {
+        self.participation() >= quorum
+    }
+
+    /// Records `voter`'s vote in `votes`, the key storage following the header, which keeps
+    /// yes voters first, grouped by option, then no voters, then abstentions. `option` picks
+    /// the candidate a yes vote goes to and is 0 otherwise. A new voter needs one free slot
+    /// after the recorded ones; casting the same vote again is rejected.
+    pub fn record_vote(
+        &mut self,
+        votes: &mut [u8],
+        voter: &Pubkey,
+        choice: VoteChoice,
+        option: u8,
+    ) -> Result<(), ProgramError> {
+        let option = if choice == VoteChoice::Yes { option } else { 0 };
+        if option >= self.options.max(1) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let recorded = self.participation() as usize;
+        if votes.len() < recorded * 32 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let existing = votes[..recorded * 32]
+            .chunks_exact(32)
+            .position(|vote| vote == voter.as_ref());
+
+        // Move the voter's key to the end of the recorded votes, out of any section
+        let end = match existing {
+            Some(idx) => {
+                let previous = self.choice_at(idx);
+                if previous == (choice, option) {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                votes[idx * 32..recorded * 32].rotate_left(32);
+                self.remove_tally(previous.0, previous.1);
+                recorded
+            }
+            None => {
+                if votes.len() < (recorded + 1) * 32 {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                votes[recorded * 32..(recorded + 1) * 32].copy_from_slice(voter.as_ref());
+                recorded + 1
+            }
+        };
+
+        // Then shift it into the end of its new section
+        let insert_at = self.section_end(choice, option);
+        votes[insert_at * 32..end * 32].rotate_right(32);
+        self.add_tally(choice, option);
+
+        Ok(())
+    }
+
+    /// Position of `delegator`'s record among the delegate records in `data`, the storage
+    /// following the header
+    pub fn delegation_index(&self, data: &[u8], delegator: &Pubkey) -> Option<usize> {
+        let start = self.participation() as usize * 32;
+        data.get(start..start + self.delegations as usize * 64)?
+            .chunks_exact(64)
+            .position(|record| &record[..32] == delegator.as_ref())
+    }
+
+    /// Notes that `delegate` cast `delegator`'s vote, in a (delegator, delegate) record after
+    /// the vote keys in `data`. Each delegator keeps one record, naming their latest delegate;
+    /// a new one needs 64 free bytes after the existing records.
+    pub fn record_delegate(
+        &mut self,
+        data: &mut [u8],
+        delegator: &Pubkey,
+        delegate: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        let idx = match self.delegation_index(data, delegator) {
+            Some(idx) => idx,
+            None => {
+                self.delegations += 1;
+                self.delegations as usize - 1
+            }
+        };
+
+        let start = self.participation() as usize * 32 + idx * 64;
+        let record = data
+            .get_mut(start..start + 64)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        record[..32].copy_from_slice(delegator.as_ref());
+        record[32..].copy_from_slice(delegate.as_ref());
+
+        Ok(())
+    }
+
+    /// Moves `current`'s vote and delegate records in `data`, the storage following the
+    /// header, to `new`. Fails if `new` already voted, which would count it twice.
+    pub fn rotate_voter(
+        &self,
+        data: &mut [u8],
+        current: &Pubkey,
+        new: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        let voters = self.participation() as usize;
+        let keys = voters + self.delegations as usize * 2;
+        let keys = data
+            .get_mut(..keys * 32)
+            .ok_or(ProgramError::InvalidAccountData)?;
+
+        if keys[..voters * 32]
+            .chunks_exact(32)
+            .any(|key| key == new.as_ref())
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // Vote keys and both halves of each delegate record are 32 byte keys alike
+        for key in keys.chunks_exact_mut(32) {
+            if key == current.as_ref() {
+                key.copy_from_slice(new.as_ref());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Option of a bound proposal that executes `transaction`, 0 for any transaction on an
+    /// unbound one
+    pub fn option_of(&self, transaction: &Pubkey) -> Result<u8, ProgramError> {
+        if self.options == 0 {
+            return Ok(0);
+        }
+
+        self.candidates[..self.options as usize]
+            .iter()
+            .position(|candidate| candidate == transaction)
+            .map(|option| option as u8)
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+
+    /// Indices in the vote storage of the yes voters for `option`
+    pub fn option_voters(&self, option: u8) -> Range<usize> {
+        let start =
+            self.section_end(VoteChoice::Yes, option) - self.option_votes[option as usize] as usize;
+        start..self.section_end(VoteChoice::Yes, option)
+    }
+
+    fn choice_at(&self, idx: usize) -> (VoteChoice, u8) {
+        for option in 0..Self::MAX_OPTIONS as u8 {
+            if idx < self.section_end(VoteChoice::Yes, option) {
+                return (VoteChoice::Yes, option);
+            }
+        }
+
+        if idx < self.section_end(VoteChoice::No, 0) {
+            (VoteChoice::No, 0)
+        } else {
+            (VoteChoice::Abstain, 0)
+        }
+    }
+
+    fn section_end(&self, choice: VoteChoice, option: u8) -> usize {
+        match choice {
+            VoteChoice::Yes => self.option_votes[..=option as usize]
+                .iter()
+                .map(|&votes| votes as usize)
+                .sum(),
+            VoteChoice::No => (self.yes_votes + self.no_votes) as usize,
+            VoteChoice::Abstain => self.participation() as usize,
+        }
+    }
+
+    fn add_tally(&mut self, choice: VoteChoice, option: u8) {
+        match choice {
+            VoteChoice::Yes => {
+                self.yes_votes += 1;
+                self.option_votes[option as usize] += 1;
+            }
+            VoteChoice::No => self.no_votes += 1,
+            VoteChoice::Abstain => self.abstain_votes += 1,
+        }
+    }
+
+    fn remove_tally(&mut self, choice: VoteChoice, option: u8) {
+        match choice {
+            VoteChoice::Yes => {
+                self.yes_votes -= 1;
+                self.option_votes[option as usize] -= 1;
+            }
+            VoteChoice::No => self.no_votes -= 1,
+            VoteChoice::Abstain => self.abstain_votes -= 1,
+        }
+    }
+
+    /// Starts the approval clock when the proposal becomes `approved` and resets it
+    /// when it stops being approved.
+    pub fn record_approval(&mut self, approved: bool, now: u64) {
+        if !approved {
+            self.approved_time = 0;
+        } else if self.approved_time == 0 {
+            self.approved_time = now;
+        }
+    }
+
     pub fn new(
         &mut self,
         proposal_id: u16,
@@ -96,13 +416,24 @@ impl ProposalState {
         bump: u8,
         created_time: u64,
         tx_type: ProposalType,
+        creator: &Pubkey,
+        optimistic: bool,
+        candidates: &[Pubkey],
+        transfer_amounts: &[u64],
     ) {
         self.proposal_id = proposal_id;
+        self.creator = *creator;
         self.expiry = expiry;
         self.created_time = created_time;
+        self.approved_time = 0;
         self.status = status;
         self.bump = bump;
         self.tx_type = tx_type;
+        self.optimistic = optimistic;
+        self.options = candidates.len() as u8;
+        self.candidates[..candidates.len()].copy_from_slice(candidates);
+        self.transfer_amounts = [Self::NO_TRANSFER; Self::MAX_OPTIONS];
+        self.transfer_amounts[..transfer_amounts.len()].copy_from_slice(transfer_amounts);
     }
 }
 
@@ -131,6 +462,27 @@ impl TryFrom<&u8> for ProposalStatus {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum VoteChoice {
+    No = 0,
+    Yes = 1,
+    Abstain = 2,
+}
+
+impl TryFrom<&u8> for VoteChoice {
+    type Error = ProgramError;
+
+    fn try_from(value: &u8) -> Result<Self, Self::Error> {
+        match *value {
+            0 => Ok(VoteChoice::No),
+            1 => Ok(VoteChoice::Yes),
+            2 => Ok(VoteChoice::Abstain),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u8)]
 pub enum ProposalType {
@@ -0,0 +1,80 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, sysvars::clock::Clock,
+};
+
+use crate::helper::account_init::StateDefinition;
+use crate::state::multisig::TimeUnit;
+
+/// Short-lived key a member registered to vote for them, so their main key can stay in cold
+/// storage. It grants no other power.
+#[derive(Pod, Zeroable, Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct SessionState {
+    /// Always `AccountType::Session`
+    pub account_type: u8,
+    /// Bump seed for the session PDA
+    pub bump: u8,
+    /// The multisig's `TimeUnit` when the session was registered
+    pub time_unit: u8,
+    pub _padding: [u8; 5],
+    pub multisig: Pubkey,
+    pub member: Pubkey,
+    pub session_key: Pubkey,
+    /// Time, in `time_unit`, after which the session key can no longer vote
+    pub expiry: u64,
+}
+
+impl StateDefinition for SessionState {
+    const LEN: usize = core::mem::size_of::<SessionState>();
+    const SEED: &'static str = "session";
+}
+
+impl SessionState {
+    pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
+        unsafe { &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self) }
+    }
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        if account_info.data_len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner() != &crate::ID {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(Self::from_account_info_unchecked(account_info))
+    }
+
+    pub fn find_pda(multisig: &Pubkey, member: &Pubkey) -> (Pubkey, u8) {
+        pinocchio::pubkey::find_program_address(
+            &[
+                Self::SEED.as_bytes(),
+                multisig.as_slice(),
+                member.as_slice(),
+            ],
+            &crate::ID,
+        )
+    }
+
+    pub fn validate_pda(&self, pda: &Pubkey) -> Result<(), ProgramError> {
+        let derived = pinocchio_pubkey::derive_address(
+            &[
+                Self::SEED.as_bytes(),
+                self.multisig.as_slice(),
+                self.member.as_slice(),
+            ],
+            Some(self.bump),
+            &crate::ID,
+        );
+        if derived != *pda {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(())
+    }
+
+    /// Whether `signer` may vote for the member at the time of `clock`
+    pub fn allows(&self, signer: &Pubkey, clock: &Clock) -> Result<bool, ProgramError> {
+        let now = TimeUnit::try_from(&self.time_unit)?.now(clock);
+        Ok(self.session_key == *signer && now <= self.expiry)
+    }
+}
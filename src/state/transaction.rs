@@ -75,6 +75,24 @@ impl TransactionState {
         Ok((program_id, instruction_data))
     }
 
+    /// Lamports moved by the stored instruction when it is a system program transfer.
+    /// Token program transfers aren't decoded: transfer tiers are in lamports, so those
+    /// are held to the plain CPI threshold.
+    pub fn transfer_amount(&self) -> Option<u64> {
+        let (program_id, data) = self.deserialize_instruction().ok()?;
+
+        // SystemInstruction::Transfer (2) and TransferWithSeed (11) both start with a u32
+        // discriminator followed by the lamports
+        if program_id != pinocchio_system::ID || data.len() < 12 {
+            return None;
+        }
+        if data[..4] != 2u32.to_le_bytes() && data[..4] != 11u32.to_le_bytes() {
+            return None;
+        }
+
+        Some(u64::from_le_bytes(data[4..12].try_into().ok()?))
+    }
+
     fn get_account_metas<'a>(
         cpi_accounts_slice: &'a [&AccountInfo],
     ) -> Result<&'a [AccountMeta<'a>], ProgramError> {
@@ -105,7 +123,7 @@ impl TransactionState {
     pub fn execute(tx_type: ProposalType, accounts: &[&AccountInfo]) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
-        let payer_acc: &AccountInfo = account_info_iter
+        let _payer_acc: &AccountInfo = account_info_iter
             .next()
             .ok_or(ProgramError::InvalidAccountData)?;
         let multisig_acc: &AccountInfo = account_info_iter
@@ -117,10 +135,13 @@ impl TransactionState {
         let transaction_acc: &AccountInfo = account_info_iter
             .next()
             .ok_or(ProgramError::InvalidAccountData)?;
-        let rent_acc: &AccountInfo = account_info_iter
+        let _rent_acc: &AccountInfo = account_info_iter
+            .next()
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let _system_program_acc: &AccountInfo = account_info_iter
             .next()
             .ok_or(ProgramError::InvalidAccountData)?;
-        let system_program_acc: &AccountInfo = account_info_iter
+        let _rent_collector_acc: &AccountInfo = account_info_iter
             .next()
             .ok_or(ProgramError::InvalidAccountData)?;
 
@@ -163,9 +184,8 @@ impl TransactionState {
             }
             ProposalType::UpdateMember => {
                 // UpdateMember
-                // Reconstruct accounts for add_member: [payer, multisig, rent, ...]
-                let add_member_accounts = &[payer_acc, multisig_acc, rent_acc, system_program_acc];
-                update_members::process_update_member(add_member_accounts, cpi_data_slice)?;
+                // Invitation accounts for pending adds follow the rent collector
+                update_members::process_update_member(accounts, cpi_data_slice)?;
 
                 multisig_state.update_transaction_index();
             }
@@ -0,0 +1,49 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::helper::account_init::StateDefinition;
+
+/// Highest nonce a member used on a relayed vote, so their signed approvals can't be replayed
+#[derive(Pod, Zeroable, Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct VoteNonceState {
+    pub multisig: Pubkey,
+    pub member: Pubkey,
+    /// Relayed votes must carry a nonce above this one
+    pub nonce: u64,
+    /// Bump seed for the vote nonce PDA
+    pub bump: u8,
+    pub _padding: [u8; 7],
+}
+
+impl StateDefinition for VoteNonceState {
+    const LEN: usize = core::mem::size_of::<VoteNonceState>();
+    const SEED: &'static str = "vote_nonce";
+}
+
+impl VoteNonceState {
+    pub fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
+        unsafe { &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self) }
+    }
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        if account_info.data_len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner() != &crate::ID {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(Self::from_account_info_unchecked(account_info))
+    }
+
+    pub fn find_pda(multisig: &Pubkey, member: &Pubkey) -> (Pubkey, u8) {
+        pinocchio::pubkey::find_program_address(
+            &[
+                Self::SEED.as_bytes(),
+                multisig.as_slice(),
+                member.as_slice(),
+            ],
+            &crate::ID,
+        )
+    }
+}
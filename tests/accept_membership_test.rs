@@ -0,0 +1,152 @@
+use litesvm::LiteSVM;
+use pinocchio_multisig::{
+    helper::StateDefinition,
+    instructions::UpdateMultisigIxData,
+    state::{InvitationState, MemberState, MultisigState},
+};
+use {
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_program::clock::Clock,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_system_program as system_program,
+    solana_sysvar::rent,
+};
+
+mod common;
+
+fn invitation_pda(program_id: Pubkey, multisig: Pubkey, invitee: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"invitation", multisig.as_ref(), invitee.as_ref()],
+        &program_id,
+    )
+    .0
+}
+
+fn accept_instruction(
+    program_id: Pubkey,
+    svm: &LiteSVM,
+    signer: Pubkey,
+    multisig_pda: Pubkey,
+    invitation: Pubkey,
+) -> Instruction {
+    let rent_collector = Pubkey::from(common::multisig_state(svm, multisig_pda).rent_collector);
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(signer, true),
+            AccountMeta::new(multisig_pda, false),
+            AccountMeta::new(invitation, false),
+            AccountMeta::new(rent_collector, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: vec![6],
+    }
+}
+
+/// Single-admin multisig with a 100 second invitation TTL that has invited `invitee`
+fn setup_invitation() -> (LiteSVM, Keypair, Pubkey, Keypair, (Pubkey, u8), Pubkey) {
+    let (mut svm, fee_payer, admin, program_id) = common::setup_svm_and_program();
+    let multisig = common::init_multisig(&mut svm, &fee_payer, program_id, &[admin.pubkey()], 1, 1);
+
+    let update = UpdateMultisigIxData {
+        value: 100,
+        update_type: 5,
+        threshold: 0,
+    };
+    common::apply_multisig_update(&mut svm, &admin, &[&admin], multisig, update);
+
+    let invitee = Keypair::new();
+    svm.airdrop(&invitee.pubkey(), 100000000).unwrap();
+    let invitation = invitation_pda(program_id, multisig.0, invitee.pubkey());
+
+    let records = [common::member_record(
+        1,
+        &[invitee.pubkey().as_ref(), &[0, 0]],
+    )];
+    let result = common::apply_member_updates(
+        &mut svm,
+        &admin,
+        &[&admin],
+        multisig,
+        &records,
+        vec![AccountMeta::new(invitation, false)],
+    );
+    assert!(result.is_ok());
+
+    // The add only opened the invitation
+    assert_eq!(common::multisig_state(&svm, multisig.0).num_members, 1);
+    let invitation_account = svm.get_account(&invitation).unwrap();
+    let invitation_state: &InvitationState =
+        bytemuck::from_bytes(&invitation_account.data[..InvitationState::LEN]);
+    assert_eq!(invitation_state.invitee, invitee.pubkey().to_bytes());
+
+    (svm, fee_payer, program_id, invitee, multisig, invitation)
+}
+
+#[test]
+fn test_accept_membership() {
+    let (mut svm, fee_payer, program_id, invitee, multisig, invitation) = setup_invitation();
+
+    // Someone else can't take the invitee's seat
+    let outsider = Keypair::new();
+    svm.airdrop(&outsider.pubkey(), 100000000).unwrap();
+    let ix = accept_instruction(program_id, &svm, outsider.pubkey(), multisig.0, invitation);
+    let result =
+        common::build_and_send_transaction_multisig(&mut svm, &fee_payer, vec![ix], &[&outsider]);
+    assert!(result.is_err());
+
+    let ix = accept_instruction(program_id, &svm, invitee.pubkey(), multisig.0, invitation);
+    let result =
+        common::build_and_send_transaction_multisig(&mut svm, &fee_payer, vec![ix], &[&invitee]);
+    assert!(result.is_ok());
+
+    let multisig_state = common::multisig_state(&svm, multisig.0);
+    assert_eq!(multisig_state.num_members, 2);
+    let members = common::members(&svm, multisig.0);
+    assert_eq!(members[1].pubkey, invitee.pubkey().to_bytes());
+    assert_eq!(
+        members[1].permissions,
+        MemberState::DEFAULT_MEMBER_PERMISSIONS
+    );
+    assert_eq!(
+        svm.get_account(&multisig.0).unwrap().data.len(),
+        MultisigState::LEN + 2 * MemberState::LEN
+    );
+
+    // The invitation is closed, so it can't be accepted twice
+    assert!(svm
+        .get_account(&invitation)
+        .map_or(true, |account| account.data.is_empty()));
+
+    svm.expire_blockhash();
+    let ix = accept_instruction(program_id, &svm, invitee.pubkey(), multisig.0, invitation);
+    let result =
+        common::build_and_send_transaction_multisig(&mut svm, &fee_payer, vec![ix], &[&invitee]);
+    assert!(result.is_err());
+    assert_eq!(common::multisig_state(&svm, multisig.0).num_members, 2);
+}
+
+#[test]
+fn test_accept_membership_after_ttl() {
+    let (mut svm, fee_payer, program_id, invitee, multisig, invitation) = setup_invitation();
+
+    let invitation_account = svm.get_account(&invitation).unwrap();
+    let expiry =
+        bytemuck::from_bytes::<InvitationState>(&invitation_account.data[..InvitationState::LEN])
+            .expiry;
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = expiry as i64 + 1;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let ix = accept_instruction(program_id, &svm, invitee.pubkey(), multisig.0, invitation);
+    let result =
+        common::build_and_send_transaction_multisig(&mut svm, &fee_payer, vec![ix], &[&invitee]);
+    assert!(result.is_err());
+
+    assert_eq!(common::multisig_state(&svm, multisig.0).num_members, 1);
+    assert!(svm.get_account(&invitation).is_some());
+}
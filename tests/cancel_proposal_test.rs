@@ -0,0 +1,184 @@
+use pinocchio_multisig::{
+    helper::StateDefinition,
+    state::{ProposalState, ProposalStatus, ProposalType},
+};
+use {
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+mod common;
+
+/// The multisig's default rent collector
+fn treasury_pda(program_id: Pubkey, multisig: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury", multisig.as_ref()], &program_id).0
+}
+
+fn cancel_instruction(
+    program_id: Pubkey,
+    canceller: Pubkey,
+    multisig: (Pubkey, u8),
+    proposal: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(canceller, true),
+            AccountMeta::new(multisig.0, false),
+            AccountMeta::new(proposal, false),
+            AccountMeta::new(treasury_pda(program_id, multisig.0), false), // rent collector
+        ],
+        data: vec![8, multisig.1],
+    }
+}
+
+#[test]
+fn test_cancel_approved_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    for admin in [&second_admin, &third_admin] {
+        common::vote(
+            &mut svm,
+            admin,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+
+    // The freed vote storage has to go to the multisig's rent collector
+    let mut wrong_collector = cancel_instruction(
+        program_id,
+        third_admin.pubkey(),
+        (pda_multisig, multisig_bump),
+        pda_proposal,
+    );
+    wrong_collector.accounts[3] = AccountMeta::new(third_admin.pubkey(), false);
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &fee_payer,
+        vec![wrong_collector],
+        &[&third_admin],
+    );
+    assert!(result.is_err());
+
+    let treasury = treasury_pda(program_id, pda_multisig);
+    let treasury_lamports_before = svm
+        .get_account(&treasury)
+        .map_or(0, |account| account.lamports);
+    assert!(svm.get_account(&pda_proposal).unwrap().data.len() > ProposalState::LEN);
+
+    svm.expire_blockhash();
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &fee_payer,
+        vec![cancel_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump),
+            pda_proposal,
+        )],
+        &[&third_admin],
+    );
+    assert!(result.is_ok());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.status, ProposalStatus::Cancelled);
+
+    // Only the header is left, the vote keys' rent went to the treasury
+    assert_eq!(proposal_account.data.len(), ProposalState::LEN);
+    let treasury_lamports_after = svm.get_account(&treasury).unwrap().lamports;
+    assert!(treasury_lamports_after > treasury_lamports_before);
+
+    // A cancelled proposal can't be cancelled again
+    svm.expire_blockhash();
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &fee_payer,
+        vec![cancel_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump),
+            pda_proposal,
+        )],
+        &[&third_admin],
+    );
+    assert!(result.is_err());
+
+    println!("✅ Success: Approved proposal cancelled!");
+}
+
+#[test]
+fn test_cancel_proposal_non_member() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_proposal, _proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    // Even a member can't cancel through an account that isn't the derived multisig
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &fee_payer,
+        vec![cancel_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump.wrapping_sub(1)),
+            pda_proposal,
+        )],
+        &[&third_admin],
+    );
+    assert!(result.is_err());
+
+    let outsider = Keypair::new();
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &fee_payer,
+        vec![cancel_instruction(
+            program_id,
+            outsider.pubkey(),
+            (pda_multisig, multisig_bump),
+            pda_proposal,
+        )],
+        &[&outsider],
+    );
+    assert!(result.is_err());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.status, ProposalStatus::Draft);
+
+    println!("✅ Success: Non-member cannot cancel a proposal!");
+}
@@ -1,11 +1,13 @@
+#![allow(dead_code)]
+
 use litesvm::{
     types::{FailedTransactionMetadata, TransactionMetadata},
     LiteSVM,
 };
 use pinocchio_multisig::{
     helper::StateDefinition,
-    instructions::{CreateProposalIxData, VoteIxData},
-    state::{ProposalState, ProposalType},
+    instructions::{CreateProposalIxData, UpdateMemberIxData, UpdateMultisigIxData, VoteIxData},
+    state::{MemberState, MultisigState, ProposalState, ProposalType},
     ID,
 };
 
@@ -85,6 +87,19 @@ pub fn create_multisig(
     fee_payer: &Keypair,
     program_id: Pubkey,
     admins: Vec<Pubkey>,
+) -> (Pubkey, u8) {
+    let num_admins = admins.len() as u8;
+    init_multisig(svm, fee_payer, program_id, &admins, num_admins, 2)
+}
+
+/// Multisig at primary seed 0 whose first `num_admins` of `members` are admins
+pub fn init_multisig(
+    svm: &mut LiteSVM,
+    fee_payer: &Keypair,
+    program_id: Pubkey,
+    members: &[Pubkey],
+    num_admins: u8,
+    min_threshold: u8,
 ) -> (Pubkey, u8) {
     let multisig_seed = [(b"multisig"), &0u16.to_le_bytes() as &[u8]];
     let (pda_multisig, multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
@@ -94,9 +109,10 @@ pub fn create_multisig(
     let init_multisig = InitMultisigIxData {
         max_expiry: 1_000_000,
         primary_seed: 0,
-        min_threshold: 2,
-        num_members: admins.len() as u8,
-        num_admins: admins.len() as u8,
+        min_threshold,
+        num_members: members.len() as u8,
+        num_admins,
+        require_member_signatures: 0,
     };
 
     let mut ix_data = vec![0u8];
@@ -110,11 +126,11 @@ pub fn create_multisig(
         AccountMeta::new(rent::ID, false),
         AccountMeta::new(system_program::id(), false),
     ];
-    let admins_accounts = admins
+    let member_accounts = members
         .iter()
-        .map(|admin| AccountMeta::new(admin.clone(), false))
+        .map(|member| AccountMeta::new(*member, false))
         .collect::<Vec<AccountMeta>>();
-    accounts.extend(admins_accounts);
+    accounts.extend(member_accounts);
 
     let init_ix = Instruction {
         program_id: Pubkey::from(ID),
@@ -135,77 +151,355 @@ pub fn create_proposal(
     multisig_pda: Pubkey,
     tx_type: ProposalType,
 ) -> (Pubkey, u8) {
+    let result = try_create_proposal(svm, fee_payer, multisig_pda, 0, tx_type);
+    assert!(result.is_ok());
+
+    proposal_pda(program_id, multisig_pda, 0)
+}
+
+pub fn proposal_pda(program_id: Pubkey, multisig_pda: Pubkey, primary_seed: u16) -> (Pubkey, u8) {
     let proposal_seed = &[
         ProposalState::SEED.as_bytes(),
         multisig_pda.as_ref(),
-        &0u16.to_le_bytes(),
+        &primary_seed.to_le_bytes(),
     ];
-    let (pda_proposal, proposal_bump) = Pubkey::find_program_address(proposal_seed, &program_id);
+    Pubkey::find_program_address(proposal_seed, &program_id)
+}
+
+/// Funded admins `(second_admin, other_admin)` of a multisig with `min_threshold` 1
+pub fn setup_two_admin_multisig() -> (LiteSVM, Keypair, Keypair, (Pubkey, u8)) {
+    let (mut svm, fee_payer, second_admin, program_id) = setup_svm_and_program();
+    let other_admin = Keypair::new();
+    svm.airdrop(&other_admin.pubkey(), 100000000).unwrap();
 
+    let members = [second_admin.pubkey(), other_admin.pubkey()];
+    let multisig = init_multisig(&mut svm, &fee_payer, program_id, &members, 2, 1);
+
+    (svm, second_admin, other_admin, multisig)
+}
+
+/// Sends a plain CreateProposal for the proposal at `primary_seed`
+pub fn try_create_proposal(
+    svm: &mut LiteSVM,
+    creator: &Keypair,
+    multisig_pda: Pubkey,
+    primary_seed: u16,
+    tx_type: ProposalType,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
     let create_proposal_data = CreateProposalIxData {
         expiry: 1_000_000,
-        primary_seed: 0,
+        primary_seed,
         tx_type,
+        optimistic: 0,
+        options: 0,
     };
 
+    try_create_proposal_with(svm, creator, multisig_pda, create_proposal_data, &[])
+}
+
+/// Sends CreateProposal with `create_proposal_data`, binding it to the `candidates`
+/// transactions
+pub fn try_create_proposal_with(
+    svm: &mut LiteSVM,
+    creator: &Keypair,
+    multisig_pda: Pubkey,
+    create_proposal_data: CreateProposalIxData,
+    candidates: &[Pubkey],
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let primary_seed = create_proposal_data.primary_seed;
+    let (pda_proposal, _) = proposal_pda(Pubkey::from(ID), multisig_pda, primary_seed);
+
     let mut ix_data = vec![2u8];
     ix_data.extend_from_slice(unsafe { to_bytes(&create_proposal_data) });
 
+    let mut accounts = vec![
+        AccountMeta::new(creator.pubkey(), true), // creator (signer)
+        AccountMeta::new(pda_proposal, false),    // proposal_account (will be created)
+        AccountMeta::new(multisig_pda, false),    // multisig_account
+        AccountMeta::new_readonly(rent::ID, false), // rent sysvar
+        AccountMeta::new_readonly(clock::ID, false), // clock sysvar
+        AccountMeta::new_readonly(system_program::id(), false), // system program
+    ];
+    accounts.extend(
+        candidates
+            .iter()
+            .map(|candidate| AccountMeta::new_readonly(*candidate, false)),
+    );
+
     let create_proposal_ix = Instruction {
         program_id: Pubkey::from(ID),
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true), // creator (signer)
-            AccountMeta::new(pda_proposal, false),      // proposal_account (will be created)
-            AccountMeta::new_readonly(multisig_pda, false), // multisig_account (readonly)
-            AccountMeta::new_readonly(rent::ID, false), // rent sysvar
-            AccountMeta::new_readonly(clock::ID, false), // clock sysvar
-            AccountMeta::new_readonly(system_program::id(), false), // system program
-        ],
+        accounts,
         data: ix_data,
     };
 
-    let result = build_and_send_transaction(svm, fee_payer, vec![create_proposal_ix]);
-    assert!(result.is_ok());
-    // println!("Created Proposal PDA: {:?}", pda_proposal);
-
-    (pda_proposal, proposal_bump)
+    build_and_send_transaction(svm, creator, vec![create_proposal_ix])
 }
 
 pub fn vote(
     svm: &mut LiteSVM,
     fee_payer: &Keypair,
-    program_id: Pubkey,
+    _program_id: Pubkey,
     multisig_pda: Pubkey,
     multisig_bump: u8,
     proposal_pda: Pubkey,
     proposal_bump: u8,
     vote: u8,
 ) -> (Pubkey, u8) {
+    let result = try_vote(
+        svm,
+        fee_payer,
+        (multisig_pda, multisig_bump),
+        (proposal_pda, proposal_bump),
+        vote,
+    );
+    println!("Vote result: {:?}", result);
+    assert!(result.is_ok());
+
+    (proposal_pda, proposal_bump)
+}
+
+/// Casts `vote` (0 no, 1 yes, 2 abstain) on the (address, bump) `proposal` of `multisig`
+pub fn try_vote(
+    svm: &mut LiteSVM,
+    voter: &Keypair,
+    multisig: (Pubkey, u8),
+    proposal: (Pubkey, u8),
+    vote: u8,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
     let vote_ix = VoteIxData {
-        multisig_bump: multisig_bump,
-        proposal_bump: proposal_bump,
+        multisig_bump: multisig.1,
+        proposal_bump: proposal.1,
         vote,
+        option: 0,
     };
 
     let mut ix_data = vec![3u8];
     ix_data.extend_from_slice(unsafe { to_bytes(&vote_ix) });
 
     let vote_ix = vec![Instruction {
-        program_id: program_id,
+        program_id: Pubkey::from(ID),
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(multisig_pda, false),
-            AccountMeta::new(proposal_pda, false),
+            AccountMeta::new(voter.pubkey(), true),
+            AccountMeta::new(multisig.0, false),
+            AccountMeta::new(proposal.0, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
         ],
         data: ix_data,
     }];
 
-    let result = build_and_send_transaction(svm, fee_payer, vote_ix);
-    println!("Vote result: {:?}", result);
+    build_and_send_transaction(svm, voter, vote_ix)
+}
+
+/// Stores `instruction_data` for `target_program` in the transaction account at `primary_seed`
+pub fn create_transaction(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    primary_seed: u16,
+    transaction_index: u64,
+    target_program: Pubkey,
+    instruction_data: &[u8],
+) -> Pubkey {
+    let mut tx_buffer = [0u8; 512];
+    tx_buffer[..32].copy_from_slice(target_program.as_ref());
+    tx_buffer[32..32 + instruction_data.len()].copy_from_slice(instruction_data);
+    let buffer_size = (32 + instruction_data.len()) as u16;
+
+    let create_transaction_data = [
+        vec![4], // discriminator for CreateTransaction instruction
+        transaction_index.to_le_bytes().to_vec(),
+        primary_seed.to_le_bytes().to_vec(),
+        tx_buffer.to_vec(),
+        buffer_size.to_le_bytes().to_vec(),
+        vec![0; 4], // 4 bytes of padding for 8-byte alignment
+    ]
+    .concat();
+
+    let (pda_transaction, _) =
+        Pubkey::find_program_address(&[b"transaction", &primary_seed.to_le_bytes()], &ID.into());
+
+    let create_transaction_ix = Instruction {
+        program_id: Pubkey::from(ID),
+        accounts: vec![
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new(pda_transaction, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: create_transaction_data,
+    };
+
+    let result = build_and_send_transaction(svm, payer, vec![create_transaction_ix]);
     assert!(result.is_ok());
-    // println!("Voted on Proposal PDA: {:?}", proposal_pda);
 
-    (proposal_pda, proposal_bump)
+    pda_transaction
+}
+
+/// Executes `transaction` for `proposal`, passing `extra_accounts` after the rent collector
+pub fn execute_transaction(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    multisig_pda: Pubkey,
+    proposal_pda: Pubkey,
+    transaction_pda: Pubkey,
+    extra_accounts: Vec<AccountMeta>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let mut accounts = vec![
+        AccountMeta::new(payer.pubkey(), true),
+        AccountMeta::new(multisig_pda, false),
+        AccountMeta::new(proposal_pda, false),
+        AccountMeta::new(transaction_pda, false),
+        AccountMeta::new_readonly(rent::ID, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(rent_collector(svm, multisig_pda), false),
+    ];
+    accounts.extend(extra_accounts);
+
+    let execute_ix = Instruction {
+        program_id: Pubkey::from(ID),
+        accounts,
+        data: vec![5], // discriminator for ExecuteTransaction instruction
+    };
+
+    svm.expire_blockhash();
+    build_and_send_transaction(svm, payer, vec![execute_ix])
+}
+
+/// Runs `data` through a `tx_type` proposal: `proposer` opens it, each of `voters` votes
+/// yes, then `proposer` executes it with `extra_accounts`. Seeds come from
+/// `next_update_seed` so updates that failed to execute don't collide with later ones.
+pub fn apply_update(
+    svm: &mut LiteSVM,
+    proposer: &Keypair,
+    voters: &[&Keypair],
+    multisig: (Pubkey, u8),
+    tx_type: ProposalType,
+    data: &[u8],
+    extra_accounts: Vec<AccountMeta>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let transaction_index = multisig_state(svm, multisig.0).transaction_index;
+    let primary_seed = next_update_seed(svm, multisig.0);
+
+    let result = try_create_proposal(svm, proposer, multisig.0, primary_seed, tx_type);
+    assert!(result.is_ok());
+    let proposal = proposal_pda(Pubkey::from(ID), multisig.0, primary_seed);
+
+    let transaction = create_transaction(
+        svm,
+        proposer,
+        primary_seed,
+        transaction_index,
+        Pubkey::from(ID),
+        data,
+    );
+
+    for voter in voters {
+        let result = try_vote(svm, voter, multisig, proposal, 1);
+        assert!(result.is_ok());
+    }
+
+    execute_transaction(
+        svm,
+        proposer,
+        multisig.0,
+        proposal.0,
+        transaction,
+        extra_accounts,
+    )
+}
+
+/// Applies a config change that `voters` are enough to approve
+pub fn apply_multisig_update(
+    svm: &mut LiteSVM,
+    proposer: &Keypair,
+    voters: &[&Keypair],
+    multisig: (Pubkey, u8),
+    update: UpdateMultisigIxData,
+) {
+    let result = apply_update(
+        svm,
+        proposer,
+        voters,
+        multisig,
+        ProposalType::UpdateMultisig,
+        unsafe { to_bytes(&update) },
+        vec![],
+    );
+    assert!(result.is_ok());
+}
+
+/// `UpdateMemberIxData` record for `operation` with `fields` packed into its member data
+pub fn member_record(operation: u8, fields: &[&[u8]]) -> UpdateMemberIxData {
+    let packed = fields.concat();
+    let mut member_data = [0u8; 64];
+    member_data[..packed.len()].copy_from_slice(&packed);
+
+    UpdateMemberIxData {
+        operation,
+        member_data,
+    }
+}
+
+/// Runs `records` as one UpdateMember batch, see `apply_update`
+pub fn apply_member_updates(
+    svm: &mut LiteSVM,
+    proposer: &Keypair,
+    voters: &[&Keypair],
+    multisig: (Pubkey, u8),
+    records: &[UpdateMemberIxData],
+    extra_accounts: Vec<AccountMeta>,
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let data = records
+        .iter()
+        .flat_map(|record| unsafe { to_bytes(record) }.to_vec())
+        .collect::<Vec<u8>>();
+
+    apply_update(
+        svm,
+        proposer,
+        voters,
+        multisig,
+        ProposalType::UpdateMember,
+        &data,
+        extra_accounts,
+    )
+}
+
+/// Proposal and transaction seeds used by `apply_update`, clear of the ones tests pick
+pub const UPDATE_SEED_BASE: u16 = 1_000;
+
+/// Seed `apply_update` opens its next proposal and transaction at
+pub fn next_update_seed(svm: &LiteSVM, multisig_pda: Pubkey) -> u16 {
+    (UPDATE_SEED_BASE..)
+        .find(|&seed| {
+            let proposal = proposal_pda(Pubkey::from(ID), multisig_pda, seed).0;
+            svm.get_account(&proposal)
+                .map_or(true, |account| account.data.is_empty())
+        })
+        .unwrap()
+}
+
+/// Members of the multisig in storage order, admins first
+pub fn members(svm: &LiteSVM, multisig_pda: Pubkey) -> Vec<MemberState> {
+    let account = svm.get_account(&multisig_pda).unwrap();
+    let members_len = multisig_state(svm, multisig_pda).num_members as usize * MemberState::LEN;
+    account.data[MultisigState::LEN..MultisigState::LEN + members_len]
+        .chunks_exact(MemberState::LEN)
+        .map(|member| *bytemuck::from_bytes::<MemberState>(member))
+        .collect()
+}
+
+/// Account the multisig hands freed rent to
+pub fn rent_collector(svm: &LiteSVM, multisig_pda: Pubkey) -> Pubkey {
+    Pubkey::from(multisig_state(svm, multisig_pda).rent_collector)
+}
+
+pub fn multisig_state(svm: &LiteSVM, multisig_pda: Pubkey) -> MultisigState {
+    let account = svm.get_account(&multisig_pda).unwrap();
+    *bytemuck::from_bytes::<MultisigState>(&account.data[..MultisigState::LEN])
+}
+
+pub fn proposal_state(svm: &LiteSVM, proposal_pda: Pubkey) -> ProposalState {
+    let account = svm.get_account(&proposal_pda).unwrap();
+    ProposalState::from_bytes(&account.data).unwrap()
 }
@@ -1,3 +1,7 @@
+use pinocchio_multisig::{
+    instructions::{CreateProposalIxData, UpdateMultisigIxData},
+    state::{Permission, ProposalState, ProposalType},
+};
 use {
     solana_instruction::{AccountMeta, Instruction},
     solana_keypair::Keypair,
@@ -20,9 +24,9 @@ fn test_create_proposal() {
     println!("pda_multisig acc : {:?}", pda_multisig);
     println!("multisig_bump: {}", multisig_bump);
 
-    let min_threshold: u8 = 2;
+    let min_threshold: u8 = 1;
     let max_expiry: u64 = 1_000_000;
-    let num_members: u8 = 0; // No initial members for this test
+    let num_members: u8 = 1; // Fee payer is the only admin for this test
     let primary_seed: u16 = 0;
 
     let data = [
@@ -31,7 +35,7 @@ fn test_create_proposal() {
         primary_seed.to_le_bytes().to_vec(),
         min_threshold.to_le_bytes().to_vec(),
         num_members.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
+        1u8.to_le_bytes().to_vec(),
         vec![0; 3], // 3 bytes of padding for 8-byte alignment
     ]
     .concat();
@@ -58,6 +62,7 @@ fn test_create_proposal() {
             AccountMeta::new(pda_treasury, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(fee_payer.pubkey(), false),
         ],
         data,
     }];
@@ -93,7 +98,7 @@ fn test_create_proposal() {
         accounts: vec![
             AccountMeta::new(fee_payer.pubkey(), true), // creator (signer)
             AccountMeta::new(pda_proposal, false),      // proposal_account (will be created)
-            AccountMeta::new_readonly(pda_multisig, false), // multisig_account (readonly)
+            AccountMeta::new(pda_multisig, false),      // multisig_account
             AccountMeta::new_readonly(rent::ID, false), // rent sysvar
             AccountMeta::new_readonly(clock::ID, false), // clock sysvar
             AccountMeta::new_readonly(system_program::id(), false), // system program
@@ -114,35 +119,17 @@ fn test_create_proposal() {
     assert!(!proposal_account.data.is_empty());
 
     // Read proposal state directly from bytes
-    let proposal_data = &proposal_account.data;
-    let proposal_id = u16::from_le_bytes([proposal_data[0], proposal_data[1]]);
-    let expiry = u64::from_le_bytes([
-        proposal_data[2],
-        proposal_data[3],
-        proposal_data[4],
-        proposal_data[5],
-        proposal_data[6],
-        proposal_data[7],
-        proposal_data[8],
-        proposal_data[9],
-    ]);
-    let created_time = u64::from_le_bytes([
-        proposal_data[10],
-        proposal_data[11],
-        proposal_data[12],
-        proposal_data[13],
-        proposal_data[14],
-        proposal_data[15],
-        proposal_data[16],
-        proposal_data[17],
-    ]);
-    let status = proposal_data[18]; // ProposalStatus as u8
-    let tx_type = proposal_data[19]; // ProposalType as u8
-    let bump = proposal_data[20];
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    let proposal_id = proposal_state.proposal_id;
+    let status = proposal_state.status as u8; // ProposalStatus as u8
+    let tx_type = proposal_state.tx_type as u8; // ProposalType as u8
 
     // Verify proposal state fields
     assert_eq!(proposal_id, proposal_primary_seed);
-    assert_eq!(expiry, expiry);
+    assert_eq!(proposal_state.expiry, proposal_state.created_time + expiry);
+    assert_eq!(proposal_state.bump, proposal_bump);
+    assert_eq!(proposal_state.approved_time, 0);
+    assert_eq!(proposal_state.creator, fee_payer.pubkey().to_bytes());
     assert_eq!(status, 0); // ProposalStatus::Draft = 0
     assert_eq!(tx_type, 0); // ProposalType::Cpi = 0
     println!("✅ Success: Proposal created with correct state data!");
@@ -182,7 +169,7 @@ fn test_create_proposal_multisig_not_initialized() {
         accounts: vec![
             AccountMeta::new(fee_payer.pubkey(), true),
             AccountMeta::new(pda_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
+            AccountMeta::new(pda_multisig, false),
             AccountMeta::new_readonly(rent::ID, false),
             AccountMeta::new_readonly(clock::ID, false), // clock sysvar
             AccountMeta::new_readonly(system_program::id(), false),
@@ -232,7 +219,7 @@ fn test_create_proposal_account_already_exists() {
         accounts: vec![
             AccountMeta::new(fee_payer.pubkey(), true),
             AccountMeta::new(pda_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
+            AccountMeta::new(pda_multisig, false),
             AccountMeta::new_readonly(rent::ID, false),
             AccountMeta::new_readonly(clock::ID, false),
             AccountMeta::new_readonly(system_program::id(), false),
@@ -318,8 +305,8 @@ fn test_create_proposal_invalid_instruction_data() {
         1_000_000u64.to_le_bytes().to_vec(),
         3u16.to_le_bytes().to_vec(),
         1u8.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
+        1u8.to_le_bytes().to_vec(),
+        1u8.to_le_bytes().to_vec(),
         vec![0; 3],
     ]
     .concat();
@@ -332,6 +319,7 @@ fn test_create_proposal_invalid_instruction_data() {
             AccountMeta::new(pda_treasury, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(fee_payer.pubkey(), false),
         ],
         data: init_data,
     }];
@@ -356,7 +344,7 @@ fn test_create_proposal_invalid_instruction_data() {
         accounts: vec![
             AccountMeta::new(fee_payer.pubkey(), true),
             AccountMeta::new(pda_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
+            AccountMeta::new(pda_multisig, false),
             AccountMeta::new_readonly(rent::ID, false),
             AccountMeta::new_readonly(clock::ID, false),
             AccountMeta::new_readonly(system_program::id(), false),
@@ -386,8 +374,8 @@ fn test_create_proposal_wrong_proposal_pda() {
         1_000_000u64.to_le_bytes().to_vec(),
         4u16.to_le_bytes().to_vec(),
         1u8.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
+        1u8.to_le_bytes().to_vec(),
+        1u8.to_le_bytes().to_vec(),
         vec![0; 3],
     ]
     .concat();
@@ -400,6 +388,7 @@ fn test_create_proposal_wrong_proposal_pda() {
             AccountMeta::new(pda_treasury, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(fee_payer.pubkey(), false),
         ],
         data: init_data,
     }];
@@ -430,7 +419,7 @@ fn test_create_proposal_wrong_proposal_pda() {
         accounts: vec![
             AccountMeta::new(fee_payer.pubkey(), true),
             AccountMeta::new(wrong_pda_proposal, false), // wrong PDA
-            AccountMeta::new_readonly(pda_multisig, false),
+            AccountMeta::new(pda_multisig, false),
             AccountMeta::new_readonly(rent::ID, false),
             AccountMeta::new_readonly(clock::ID, false),
             AccountMeta::new_readonly(system_program::id(), false),
@@ -516,7 +505,7 @@ fn test_create_proposal_non_admin_member() {
         accounts: vec![
             AccountMeta::new(second_admin.pubkey(), true), // normal member as creator (signer)
             AccountMeta::new(pda_proposal, false),         // proposal account
-            AccountMeta::new_readonly(pda_multisig, false), // multisig account
+            AccountMeta::new(pda_multisig, false),         // multisig account
             AccountMeta::new_readonly(rent::ID, false),    // rent sysvar
             AccountMeta::new_readonly(clock::ID, false),
             AccountMeta::new_readonly(system_program::id(), false), // system program
@@ -532,3 +521,229 @@ fn test_create_proposal_non_admin_member() {
         "Expected error for non-admin member creating proposal"
     );
 }
+
+#[test]
+fn test_create_proposal_expiry_capped_by_max_expiry() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    // max_expiry is 1_000_000 seconds
+    let (pda_multisig, _multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let mut pda_proposals = vec![];
+
+    // Longer than max_expiry, then 0 for the default period
+    for (proposal_primary_seed, expiry) in [(0u16, 5_000_000u64), (1u16, 0u64)] {
+        let proposal_seed = [
+            b"proposal".as_ref(),
+            pda_multisig.as_ref(),
+            &proposal_primary_seed.to_le_bytes(),
+        ];
+        let (pda_proposal, _proposal_bump) =
+            Pubkey::find_program_address(&proposal_seed, &program_id);
+
+        let create_proposal_data = [
+            vec![2],                                      // discriminator (CreateProposal)
+            expiry.to_le_bytes().to_vec(),                // expiry: u64 (8 bytes)
+            proposal_primary_seed.to_le_bytes().to_vec(), // primary_seed: u16 (2 bytes)
+            vec![0],                                      // tx_type: u8 (1 byte)
+            vec![0; 5],
+        ]
+        .concat();
+
+        let create_proposal_instruction = vec![Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(second_admin.pubkey(), true),
+                AccountMeta::new(pda_proposal, false),
+                AccountMeta::new(pda_multisig, false),
+                AccountMeta::new_readonly(rent::ID, false),
+                AccountMeta::new_readonly(clock::ID, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: create_proposal_data,
+        }];
+
+        let result = common::build_and_send_transaction(
+            &mut svm,
+            &second_admin,
+            create_proposal_instruction,
+        );
+        assert!(result.is_ok());
+
+        pda_proposals.push(pda_proposal);
+    }
+
+    for pda_proposal in pda_proposals {
+        let proposal_account = svm.get_account(&pda_proposal).unwrap();
+        let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+        assert_eq!(
+            proposal_state.expiry,
+            proposal_state.created_time + 1_000_000
+        );
+    }
+
+    println!("✅ Success: Proposal expiry capped by max_expiry!");
+}
+
+#[test]
+fn test_create_optimistic_proposal_without_challenge_window() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    // No challenge window or reject threshold configured
+    let (pda_multisig, _multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let proposal_primary_seed: u16 = 0;
+    let proposal_seed = [
+        b"proposal".as_ref(),
+        pda_multisig.as_ref(),
+        &proposal_primary_seed.to_le_bytes(),
+    ];
+    let (pda_proposal, _proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
+
+    let create_proposal_data = [
+        vec![2],                                      // discriminator (CreateProposal)
+        0u64.to_le_bytes().to_vec(),                  // expiry: u64 (8 bytes)
+        proposal_primary_seed.to_le_bytes().to_vec(), // primary_seed: u16 (2 bytes)
+        vec![0],                                      // tx_type: u8 (1 byte)
+        vec![1],                                      // optimistic: u8 (1 byte)
+        vec![0; 4],
+    ]
+    .concat();
+
+    let create_proposal_instruction = vec![Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(second_admin.pubkey(), true),
+            AccountMeta::new(pda_proposal, false),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(clock::ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: create_proposal_data,
+    }];
+
+    let result =
+        common::build_and_send_transaction(&mut svm, &second_admin, create_proposal_instruction);
+    assert!(result.is_err());
+
+    println!("✅ Success: Optimistic proposal rejected without a challenge window!");
+}
+
+#[test]
+fn test_create_proposal_requires_permissions() {
+    let (mut svm, second_admin, _, multisig) = common::setup_two_admin_multisig();
+
+    // Optimistic proposals are enabled, so only permissions stand in the way
+    let challenge_window = UpdateMultisigIxData {
+        value: 100,
+        update_type: 17, // 17 for update challenge window
+        threshold: 0,
+    };
+    common::apply_multisig_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        challenge_window,
+    );
+    let reject_threshold = UpdateMultisigIxData {
+        value: 0,
+        update_type: 10, // 10 for update reject threshold
+        threshold: 1,
+    };
+    common::apply_multisig_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        reject_threshold,
+    );
+
+    let initiator = Keypair::new();
+    svm.airdrop(&initiator.pubkey(), 100000000).unwrap();
+    let permissions = Permission::Initiate as u8 | Permission::Vote as u8;
+    let add_initiator = common::member_record(1, &[initiator.pubkey().as_ref(), &[0, permissions]]);
+    let result = common::apply_member_updates(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        &[add_initiator],
+        vec![],
+    );
+    assert!(result.is_ok());
+
+    // Outsiders can't create any proposal
+    let outsider = Keypair::new();
+    svm.airdrop(&outsider.pubkey(), 100000000).unwrap();
+    let result = common::try_create_proposal(&mut svm, &outsider, multisig.0, 1, ProposalType::Cpi);
+    assert!(result.is_err());
+
+    // Initiate covers plain proposals but not optimistic ones
+    let transaction_index = common::multisig_state(&svm, multisig.0).transaction_index;
+    let transaction = common::create_transaction(
+        &mut svm,
+        &second_admin,
+        40,
+        transaction_index,
+        system_program::id(),
+        &[],
+    );
+    let optimistic = CreateProposalIxData {
+        expiry: 1_000_000,
+        primary_seed: 1,
+        tx_type: ProposalType::Cpi,
+        optimistic: 1,
+        options: 1,
+    };
+    let result = common::try_create_proposal_with(
+        &mut svm,
+        &initiator,
+        multisig.0,
+        optimistic,
+        &[transaction],
+    );
+    assert!(result.is_err());
+    let result =
+        common::try_create_proposal(&mut svm, &initiator, multisig.0, 1, ProposalType::Cpi);
+    assert!(result.is_ok());
+
+    // Admins may propose optimistically, but only CPI proposals
+    let optimistic_update = CreateProposalIxData {
+        primary_seed: 2,
+        tx_type: ProposalType::UpdateMultisig,
+        ..optimistic
+    };
+    let result = common::try_create_proposal_with(
+        &mut svm,
+        &second_admin,
+        multisig.0,
+        optimistic_update,
+        &[transaction],
+    );
+    assert!(result.is_err());
+    let optimistic_cpi = CreateProposalIxData {
+        primary_seed: 2,
+        ..optimistic
+    };
+    let result = common::try_create_proposal_with(
+        &mut svm,
+        &second_admin,
+        multisig.0,
+        optimistic_cpi,
+        &[transaction],
+    );
+    assert!(result.is_ok());
+    assert_eq!(common::multisig_state(&svm, multisig.0).open_optimistic, 1);
+}
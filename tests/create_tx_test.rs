@@ -1,3 +1,4 @@
+use pinocchio_multisig::{helper::StateDefinition, state::TransactionState};
 use {
     solana_instruction::{AccountMeta, Instruction},
     solana_pubkey::Pubkey,
@@ -233,3 +234,49 @@ fn test_create_transaction_account_already_initialized() {
 
     println!("✅ Success: Transaction creation correctly rejected already initialized account!");
 }
+
+#[test]
+fn test_transaction_transfer_amount() {
+    let (mut svm, fee_payer, _second_admin, _program_id) = common::setup_svm_and_program();
+
+    let token_program: Pubkey = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        .parse()
+        .unwrap();
+    let lamports = 7_000u64.to_le_bytes().to_vec();
+
+    // System transfers are decoded with or without a seed, token transfers are not
+    let cases = [
+        (
+            system_program::id(),
+            [2u32.to_le_bytes().to_vec(), lamports.clone()].concat(),
+            Some(7_000),
+        ),
+        (
+            system_program::id(),
+            [11u32.to_le_bytes().to_vec(), lamports.clone()].concat(),
+            Some(7_000),
+        ),
+        (
+            system_program::id(),
+            [0u32.to_le_bytes().to_vec(), lamports.clone()].concat(),
+            None,
+        ),
+        (token_program, [vec![3], lamports].concat(), None),
+    ];
+
+    for (idx, (target_program, instruction_data, expected)) in cases.into_iter().enumerate() {
+        let pda_transaction = common::create_transaction(
+            &mut svm,
+            &fee_payer,
+            50 + idx as u16,
+            0,
+            target_program,
+            &instruction_data,
+        );
+
+        let transaction_account = svm.get_account(&pda_transaction).unwrap();
+        let transaction: &TransactionState =
+            bytemuck::from_bytes(&transaction_account.data[..TransactionState::LEN]);
+        assert_eq!(transaction.transfer_amount(), expected);
+    }
+}
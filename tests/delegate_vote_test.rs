@@ -0,0 +1,280 @@
+use pinocchio_multisig::{
+    helper::{utils::to_bytes, StateDefinition},
+    instructions::VoteIxData,
+    state::{AccountType, DelegationState, ProposalState, ProposalType},
+};
+use {
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_system_program as system_program,
+    solana_sysvar::rent,
+};
+
+mod common;
+
+fn delegate_instruction(
+    program_id: Pubkey,
+    delegator: Pubkey,
+    multisig: (Pubkey, u8),
+    delegate: Pubkey,
+    proposal_types: u8,
+) -> Instruction {
+    let (pda_delegation, _) = Pubkey::find_program_address(
+        &[b"delegation", multisig.0.as_ref(), delegator.as_ref()],
+        &program_id,
+    );
+
+    let data = [
+        vec![10],                    // discriminator for DelegateVote instruction
+        0u64.to_le_bytes().to_vec(), // duration: no limit
+        vec![proposal_types, multisig.1],
+        vec![0; 6], // 6 bytes of padding for 8-byte alignment
+    ]
+    .concat();
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(delegator, true),
+            AccountMeta::new_readonly(multisig.0, false),
+            AccountMeta::new(pda_delegation, false),
+            AccountMeta::new_readonly(delegate, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+fn delegated_vote_instruction(
+    program_id: Pubkey,
+    delegate: Pubkey,
+    multisig: Pubkey,
+    multisig_bump: u8,
+    proposal: Pubkey,
+    proposal_bump: u8,
+    delegation: Pubkey,
+) -> Instruction {
+    let vote_ix = VoteIxData {
+        multisig_bump,
+        proposal_bump,
+        vote: 1,
+        option: 0,
+    };
+
+    let mut ix_data = vec![3u8];
+    ix_data.extend_from_slice(unsafe { to_bytes(&vote_ix) });
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(delegate, true),
+            AccountMeta::new(multisig, false),
+            AccountMeta::new(proposal, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(delegation, false),
+        ],
+        data: ix_data,
+    }
+}
+
+#[test]
+fn test_delegate_vote() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    let delegate = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    svm.airdrop(&delegate.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    // The multisig must be the PDA of its own primary seed
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &third_admin,
+        vec![delegate_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump.wrapping_sub(1)),
+            delegate.pubkey(),
+            1 << ProposalType::Cpi as u8,
+        )],
+    );
+    assert!(result.is_err());
+
+    // Third admin delegates their vote on CPI proposals
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &third_admin,
+        vec![delegate_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump),
+            delegate.pubkey(),
+            1 << ProposalType::Cpi as u8,
+        )],
+    );
+    assert!(result.is_ok());
+
+    let (pda_delegation, _) = Pubkey::find_program_address(
+        &[
+            b"delegation",
+            pda_multisig.as_ref(),
+            third_admin.pubkey().as_ref(),
+        ],
+        &program_id,
+    );
+    let delegation_account = svm.get_account(&pda_delegation).unwrap();
+    let delegation: &DelegationState =
+        bytemuck::from_bytes(&delegation_account.data[..DelegationState::LEN]);
+    assert_eq!(delegation.account_type, AccountType::Delegation as u8);
+    assert_eq!(delegation.delegator, third_admin.pubkey().to_bytes());
+    assert_eq!(delegation.delegate, delegate.pubkey().to_bytes());
+    assert_eq!(delegation.expiry, 0);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &delegate,
+        vec![delegated_vote_instruction(
+            program_id,
+            delegate.pubkey(),
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            pda_delegation,
+        )],
+    );
+    assert!(result.is_ok());
+
+    // The vote counts as the delegator's, with the delegate recorded next to it
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_data = &proposal_account.data;
+    let proposal_state = ProposalState::from_bytes(proposal_data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 1);
+    assert_eq!(proposal_state.delegations, 1);
+    assert_eq!(proposal_data.len(), ProposalState::LEN + 32 + 64);
+
+    let storage = &proposal_data[ProposalState::LEN..];
+    assert_eq!(&storage[..32], third_admin.pubkey().as_ref());
+    assert_eq!(&storage[32..64], third_admin.pubkey().as_ref());
+    assert_eq!(&storage[64..96], delegate.pubkey().as_ref());
+
+    // The second admin's own vote goes in front of the delegate record
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        0,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let storage = &proposal_account.data[ProposalState::LEN..];
+    assert_eq!(&storage[32..64], second_admin.pubkey().as_ref());
+    assert_eq!(&storage[64..96], third_admin.pubkey().as_ref());
+    assert_eq!(&storage[96..128], delegate.pubkey().as_ref());
+
+    println!("✅ Success: Delegate voted for the delegator!");
+}
+
+#[test]
+fn test_delegate_vote_revoked() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    let delegate = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    svm.airdrop(&delegate.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    for proposal_types in [1 << ProposalType::Cpi as u8, 0] {
+        let result = common::build_and_send_transaction(
+            &mut svm,
+            &third_admin,
+            vec![delegate_instruction(
+                program_id,
+                third_admin.pubkey(),
+                (pda_multisig, multisig_bump),
+                delegate.pubkey(),
+                proposal_types,
+            )],
+        );
+        assert!(result.is_ok());
+    }
+
+    let (pda_delegation, _) = Pubkey::find_program_address(
+        &[
+            b"delegation",
+            pda_multisig.as_ref(),
+            third_admin.pubkey().as_ref(),
+        ],
+        &program_id,
+    );
+    assert!(svm
+        .get_account(&pda_delegation)
+        .map_or(true, |account| account.data.is_empty()));
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    // Neither a revoked delegation nor the delegate's own key can vote
+    for instruction in [
+        delegated_vote_instruction(
+            program_id,
+            delegate.pubkey(),
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            pda_delegation,
+        ),
+        {
+            let mut instruction = delegated_vote_instruction(
+                program_id,
+                delegate.pubkey(),
+                pda_multisig,
+                multisig_bump,
+                pda_proposal,
+                proposal_bump,
+                pda_delegation,
+            );
+            instruction.accounts.truncate(5);
+            instruction
+        },
+    ] {
+        let result = common::build_and_send_transaction(&mut svm, &delegate, vec![instruction]);
+        assert!(result.is_err());
+    }
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 0);
+
+    println!("✅ Success: Revoked delegation can no longer vote!");
+}
@@ -1,13 +1,22 @@
 use pinocchio_multisig::helper::account_init::StateDefinition;
 use pinocchio_multisig::{
     helper::to_bytes,
-    instructions::{UpdateMemberIxData, UpdateMultisigIxData},
-    state::{MemberState, MultisigState},
+    instructions::{CreateProposalIxData, UpdateMemberIxData, UpdateMultisigIxData},
+    state::{
+        MemberState, MultisigState, ProposalState, ProposalStatus, ProposalType, RemovalPolicy,
+        TimeUnit,
+    },
+    ID,
 };
 
+use litesvm::{
+    types::{FailedTransactionMetadata, TransactionMetadata},
+    LiteSVM,
+};
 use {
     solana_instruction::{AccountMeta, Instruction},
     solana_keypair::Keypair,
+    solana_program::clock::Clock,
     solana_pubkey::Pubkey,
     solana_signer::Signer,
     solana_system_program as system_program,
@@ -32,7 +41,7 @@ fn test_execute_transaction_update_member() {
     println!("pda_multisig acc : {:?}", pda_multisig);
     println!("multisig_bump: {}", multisig_bump);
 
-    let min_threshold: u8 = 0;
+    let min_threshold: u8 = 1;
     let max_expiry: u64 = 1_000_000;
     let num_members: u8 = 2;
     let primary_seed: u16 = 0;
@@ -82,7 +91,7 @@ fn test_execute_transaction_update_member() {
         pda_multisig.as_ref(),
         &proposal_primary_seed.to_le_bytes(),
     ];
-    let (pda_proposal, _proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
+    let (pda_proposal, proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
 
     let expiry: u64 = 3758794966; // Feb 09 2089
     let tx_type: u8 = 1;
@@ -101,7 +110,7 @@ fn test_execute_transaction_update_member() {
         accounts: vec![
             AccountMeta::new(second_admin_pubkey, true), // creator (signer)
             AccountMeta::new(pda_proposal, false),       // proposal_account (will be created)
-            AccountMeta::new_readonly(pda_multisig, false), // multisig_account (readonly)
+            AccountMeta::new(pda_multisig, false),       // multisig_account
             AccountMeta::new_readonly(rent::ID, false),  // rent sysvar
             AccountMeta::new_readonly(clock::ID, false), // clock sysvar
             AccountMeta::new_readonly(system_program::id(), false), // system program
@@ -123,7 +132,7 @@ fn test_execute_transaction_update_member() {
     let update_member_data = UpdateMemberIxData {
         operation: 1, // 1 for add member
         member_data: {
-            let mut data = [0u8; 33];
+            let mut data = [0u8; 64];
             data[..32].copy_from_slice(third_member.pubkey().as_ref());
             data[32] = 0; // 0 = normal member, 1 = admin
             data
@@ -173,24 +182,40 @@ fn test_execute_transaction_update_member() {
     assert!(result.is_ok());
     println!("✅ Transaction created with UpdateMember instruction");
 
+    // Approve the proposal
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
     // Execute the transaction
     let execute_transaction_data = vec![5]; // discriminator for ExecuteTransaction instruction
 
     let execute_transaction_instruction = vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true), // payer (signer)
-            AccountMeta::new(pda_multisig, false),      // multisig
-            AccountMeta::new(pda_proposal, false),      // proposal
-            AccountMeta::new(pda_transaction, false),   // transaction
-            AccountMeta::new(rent::ID, false),          // rent for add_member
+            AccountMeta::new(second_admin.pubkey(), true), // payer (signer)
+            AccountMeta::new(pda_multisig, false),         // multisig
+            AccountMeta::new(pda_proposal, false),         // proposal
+            AccountMeta::new(pda_transaction, false),      // transaction
+            AccountMeta::new(rent::ID, false),             // rent for add_member
             AccountMeta::new(system_program::id(), false), // system program for add_member
+            AccountMeta::new(common::rent_collector(&svm, pda_multisig), false), // rent collector for the vote storage
         ],
         data: execute_transaction_data,
     }];
 
-    let result =
-        common::build_and_send_transaction(&mut svm, &fee_payer, execute_transaction_instruction);
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &second_admin,
+        execute_transaction_instruction,
+    );
     println!("Execute transaction result: {:?}", result);
     assert!(result.is_ok());
     println!("✅ Transaction executed successfully");
@@ -241,7 +266,7 @@ fn test_execute_transaction_update_member() {
         pda_multisig.as_ref(),
         &remove_proposal_primary_seed.to_le_bytes(),
     ];
-    let (pda_remove_proposal, _remove_proposal_bump) =
+    let (pda_remove_proposal, remove_proposal_bump) =
         Pubkey::find_program_address(&remove_proposal_seed, &program_id);
 
     let remove_expiry: u64 = 3758794966; // Feb 09 2089
@@ -261,7 +286,7 @@ fn test_execute_transaction_update_member() {
         accounts: vec![
             AccountMeta::new(second_admin_pubkey, true), // creator (signer)
             AccountMeta::new(pda_remove_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
+            AccountMeta::new(pda_multisig, false),
             AccountMeta::new_readonly(rent::ID, false),
             AccountMeta::new_readonly(clock::ID, false),
             AccountMeta::new_readonly(system_program::id(), false),
@@ -283,7 +308,7 @@ fn test_execute_transaction_update_member() {
     let remove_member_data = UpdateMemberIxData {
         operation: 2, // 2 for remove member
         member_data: {
-            let mut data = [0u8; 33];
+            let mut data = [0u8; 64];
             data[..32].copy_from_slice(third_member.pubkey().as_ref());
             data[32] = 0; // not used for remove
             data
@@ -333,25 +358,43 @@ fn test_execute_transaction_update_member() {
     assert!(result.is_ok());
     println!("✅ Remove transaction created");
 
+    // Approve the proposal
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_remove_proposal,
+        remove_proposal_bump,
+        1,
+    );
+
     // Execute the remove transaction
     let execute_remove_transaction_data = vec![5];
 
     let execute_remove_transaction_instruction = vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(second_admin.pubkey(), true),
             AccountMeta::new(pda_multisig, false),
             AccountMeta::new(pda_remove_proposal, false),
             AccountMeta::new(pda_remove_transaction, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(pda_treasury, false), // rent collector for the freed slot
         ],
         data: execute_remove_transaction_data,
     }];
 
+    let treasury_lamports_before = svm
+        .get_account(&pda_treasury)
+        .map(|account| account.lamports)
+        .unwrap_or(0);
+
     let result = common::build_and_send_transaction(
         &mut svm,
-        &fee_payer,
+        &second_admin,
         execute_remove_transaction_instruction,
     );
     assert!(result.is_ok());
@@ -363,6 +406,11 @@ fn test_execute_transaction_update_member() {
     let multisig_state_bytes = &multisig_data[..MultisigState::LEN];
     let multisig_state: &MultisigState = bytemuck::from_bytes(multisig_state_bytes);
     assert_eq!(multisig_state.num_members, 2); // Should be 2 now (3 - 1)
+    assert_eq!(multisig_state.rent_collector, pda_treasury.to_bytes());
+
+    // The freed slot's rent was swept to the treasury
+    let treasury_lamports_after = svm.get_account(&pda_treasury).unwrap().lamports;
+    assert!(treasury_lamports_after > treasury_lamports_before);
 
     // Add an admin and verify the pattern: admin1 | admin2 | ... | normal member1 | ...
     let add_admin_proposal_primary_seed: u16 = 5;
@@ -371,7 +419,7 @@ fn test_execute_transaction_update_member() {
         pda_multisig.as_ref(),
         &add_admin_proposal_primary_seed.to_le_bytes(),
     ];
-    let (pda_add_admin_proposal, _add_admin_proposal_bump) =
+    let (pda_add_admin_proposal, add_admin_proposal_bump) =
         Pubkey::find_program_address(&add_admin_proposal_seed, &program_id);
 
     let add_admin_expiry: u64 = 3758794966; // Feb 09 2089
@@ -391,7 +439,7 @@ fn test_execute_transaction_update_member() {
         accounts: vec![
             AccountMeta::new(second_admin_pubkey, true),
             AccountMeta::new(pda_add_admin_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
+            AccountMeta::new(pda_multisig, false),
             AccountMeta::new_readonly(rent::ID, false),
             AccountMeta::new_readonly(clock::ID, false),
             AccountMeta::new_readonly(system_program::id(), false),
@@ -413,7 +461,7 @@ fn test_execute_transaction_update_member() {
     let add_admin_data = UpdateMemberIxData {
         operation: 1, // 1 for add member
         member_data: {
-            let mut data = [0u8; 33];
+            let mut data = [0u8; 64];
             data[..32].copy_from_slice(fifth_member.pubkey().as_ref());
             data[32] = 1; // 1 = admin
             data
@@ -463,25 +511,38 @@ fn test_execute_transaction_update_member() {
     assert!(result.is_ok());
     println!("✅ Add admin transaction created");
 
+    // Approve the proposal
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_add_admin_proposal,
+        add_admin_proposal_bump,
+        1,
+    );
+
     // Execute the add admin transaction
     let execute_add_admin_transaction_data = vec![5];
 
     let execute_add_admin_transaction_instruction = vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(second_admin.pubkey(), true),
             AccountMeta::new(pda_multisig, false),
             AccountMeta::new(pda_add_admin_proposal, false),
             AccountMeta::new(pda_add_admin_transaction, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(common::rent_collector(&svm, pda_multisig), false), // rent collector for the vote storage
         ],
         data: execute_add_admin_transaction_data,
     }];
 
     let result = common::build_and_send_transaction(
         &mut svm,
-        &fee_payer,
+        &second_admin,
         execute_add_admin_transaction_instruction,
     );
     assert!(result.is_ok());
@@ -527,7 +588,7 @@ fn test_execute_transaction_update_member() {
         pda_multisig.as_ref(),
         &add_normal_proposal_primary_seed.to_le_bytes(),
     ];
-    let (pda_add_normal_proposal, _add_normal_proposal_bump) =
+    let (pda_add_normal_proposal, add_normal_proposal_bump) =
         Pubkey::find_program_address(&add_normal_proposal_seed, &program_id);
 
     let add_normal_expiry: u64 = 3758794966; // Feb 09 2089
@@ -547,7 +608,7 @@ fn test_execute_transaction_update_member() {
         accounts: vec![
             AccountMeta::new(second_admin_pubkey, true), // creator (signer)
             AccountMeta::new(pda_add_normal_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
+            AccountMeta::new(pda_multisig, false),
             AccountMeta::new_readonly(rent::ID, false),
             AccountMeta::new_readonly(clock::ID, false),
             AccountMeta::new_readonly(system_program::id(), false),
@@ -569,7 +630,7 @@ fn test_execute_transaction_update_member() {
     let add_normal_data = UpdateMemberIxData {
         operation: 1, // 1 for add member
         member_data: {
-            let mut data = [0u8; 33];
+            let mut data = [0u8; 64];
             data[..32].copy_from_slice(sixth_member.pubkey().as_ref());
             data[32] = 0; // 0 = normal member
             data
@@ -618,25 +679,38 @@ fn test_execute_transaction_update_member() {
     );
     assert!(result.is_ok());
 
+    // Approve the proposal
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_add_normal_proposal,
+        add_normal_proposal_bump,
+        1,
+    );
+
     // Execute the add normal transaction
     let execute_add_normal_transaction_data = vec![5];
 
     let execute_add_normal_transaction_instruction = vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(second_admin.pubkey(), true),
             AccountMeta::new(pda_multisig, false),
             AccountMeta::new(pda_add_normal_proposal, false),
             AccountMeta::new(pda_add_normal_transaction, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(common::rent_collector(&svm, pda_multisig), false), // rent collector for the vote storage
         ],
         data: execute_add_normal_transaction_data,
     }];
 
     let result = common::build_and_send_transaction(
         &mut svm,
-        &fee_payer,
+        &second_admin,
         execute_add_normal_transaction_instruction,
     );
     assert!(result.is_ok());
@@ -649,7 +723,7 @@ fn test_execute_transaction_update_member() {
         pda_multisig.as_ref(),
         &remove_first_admin_proposal_primary_seed.to_le_bytes(),
     ];
-    let (pda_remove_first_admin_proposal, _remove_first_admin_proposal_bump) =
+    let (pda_remove_first_admin_proposal, remove_first_admin_proposal_bump) =
         Pubkey::find_program_address(&remove_first_admin_proposal_seed, &program_id);
 
     let remove_first_admin_expiry: u64 = 3758794966; // Feb 09 2089
@@ -671,7 +745,7 @@ fn test_execute_transaction_update_member() {
         accounts: vec![
             AccountMeta::new(second_admin_pubkey, true), // creator (signer)
             AccountMeta::new(pda_remove_first_admin_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
+            AccountMeta::new(pda_multisig, false),
             AccountMeta::new_readonly(rent::ID, false),
             AccountMeta::new_readonly(clock::ID, false),
             AccountMeta::new_readonly(system_program::id(), false),
@@ -694,7 +768,7 @@ fn test_execute_transaction_update_member() {
     let remove_first_admin_data = UpdateMemberIxData {
         operation: 2, // 2 for remove member
         member_data: {
-            let mut data = [0u8; 33];
+            let mut data = [0u8; 64];
             data[..32].copy_from_slice(second_admin_pubkey.as_ref());
             data[32] = 0; // not used for remove
             data
@@ -747,25 +821,38 @@ fn test_execute_transaction_update_member() {
     assert!(result.is_ok());
     println!("✅ Remove first admin transaction created");
 
+    // Approve the proposal
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_remove_first_admin_proposal,
+        remove_first_admin_proposal_bump,
+        1,
+    );
+
     // Execute the remove first admin transaction
     let execute_remove_first_admin_transaction_data = vec![5];
 
     let execute_remove_first_admin_transaction_instruction = vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(second_admin.pubkey(), true),
             AccountMeta::new(pda_multisig, false),
             AccountMeta::new(pda_remove_first_admin_proposal, false),
             AccountMeta::new(pda_remove_first_admin_transaction, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(pda_treasury, false), // rent collector for the freed slot
         ],
         data: execute_remove_first_admin_transaction_data,
     }];
 
     let result = common::build_and_send_transaction(
         &mut svm,
-        &fee_payer,
+        &second_admin,
         execute_remove_first_admin_transaction_instruction,
     );
     assert!(result.is_ok());
@@ -824,7 +911,7 @@ fn test_execute_transaction_update_member() {
         pda_multisig.as_ref(),
         &remove_fifth_member_proposal_primary_seed.to_le_bytes(),
     ];
-    let (pda_remove_fifth_member_proposal, _remove_fifth_member_proposal_bump) =
+    let (pda_remove_fifth_member_proposal, remove_fifth_member_proposal_bump) =
         Pubkey::find_program_address(&remove_fifth_member_proposal_seed, &program_id);
 
     let remove_fifth_member_expiry: u64 = 3758794966; // Feb 09 2089
@@ -846,7 +933,7 @@ fn test_execute_transaction_update_member() {
         accounts: vec![
             AccountMeta::new(fourth_member.pubkey(), true),
             AccountMeta::new(pda_remove_fifth_member_proposal, false),
-            AccountMeta::new_readonly(pda_multisig, false),
+            AccountMeta::new(pda_multisig, false),
             AccountMeta::new_readonly(rent::ID, false),
             AccountMeta::new_readonly(clock::ID, false),
             AccountMeta::new_readonly(system_program::id(), false),
@@ -875,7 +962,7 @@ fn test_execute_transaction_update_member() {
     let remove_fifth_member_data = UpdateMemberIxData {
         operation: 2, // 2 for remove member
         member_data: {
-            let mut data = [0u8; 33];
+            let mut data = [0u8; 64];
             data[..32].copy_from_slice(fifth_member.pubkey().as_ref());
             data[32] = 0; // not used for remove
             data
@@ -928,25 +1015,38 @@ fn test_execute_transaction_update_member() {
     assert!(result.is_ok());
     println!("✅ Remove second admin transaction created");
 
+    // Approve the proposal
+    common::vote(
+        &mut svm,
+        &fourth_member,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_remove_fifth_member_proposal,
+        remove_fifth_member_proposal_bump,
+        1,
+    );
+
     // Execute the remove second admin transaction
     let execute_remove_fifth_member_transaction_data = vec![5];
 
     let execute_remove_fifth_member_transaction_instruction = vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(fourth_member.pubkey(), true),
             AccountMeta::new(pda_multisig, false),
             AccountMeta::new(pda_remove_fifth_member_proposal, false),
             AccountMeta::new(pda_remove_fifth_member_transaction, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(pda_treasury, false), // rent collector for the freed slot
         ],
         data: execute_remove_fifth_member_transaction_data,
     }];
 
     let result = common::build_and_send_transaction(
         &mut svm,
-        &fee_payer,
+        &fourth_member,
         execute_remove_fifth_member_transaction_instruction,
     );
     assert!(result.is_ok());
@@ -992,6 +1092,330 @@ fn test_execute_transaction_update_member() {
     println!("✅ All admin removal tests completed successfully!");
 }
 
+#[test]
+fn test_execute_transaction_promote_and_demote_member() {
+    let (mut svm, fee_payer, admin, program_id) = common::setup_svm_and_program();
+    let member = Keypair::new();
+    svm.airdrop(&member.pubkey(), 1_000_000_000).unwrap();
+    let members = [admin.pubkey(), member.pubkey()];
+    let multisig = common::init_multisig(&mut svm, &fee_payer, program_id, &members, 1, 1);
+
+    // Promoting moves the member to the end of the admin section with every permission
+    let promote = common::member_record(3, &[member.pubkey().as_ref()]);
+    let result =
+        common::apply_member_updates(&mut svm, &admin, &[&admin], multisig, &[promote], vec![]);
+    assert!(result.is_ok());
+
+    let members = common::members(&svm, multisig.0);
+    assert_eq!(common::multisig_state(&svm, multisig.0).admin_counter, 2);
+    assert_eq!(members[1].pubkey, member.pubkey().to_bytes());
+    assert_eq!(members[1].permissions, MemberState::ALL_PERMISSIONS);
+
+    // Demoting swaps the admin into the last admin slot, which becomes the first member slot
+    let demote = common::member_record(4, &[admin.pubkey().as_ref()]);
+    let result =
+        common::apply_member_updates(&mut svm, &admin, &[&admin], multisig, &[demote], vec![]);
+    assert!(result.is_ok());
+
+    let members = common::members(&svm, multisig.0);
+    assert_eq!(common::multisig_state(&svm, multisig.0).admin_counter, 1);
+    assert_eq!(members[0].pubkey, member.pubkey().to_bytes());
+    assert_eq!(members[1].pubkey, admin.pubkey().to_bytes());
+    assert_eq!(
+        members[1].permissions,
+        MemberState::DEFAULT_MEMBER_PERMISSIONS
+    );
+
+    // Promoting an admin, demoting a normal member or demoting the last admin all fail
+    let records = [
+        common::member_record(3, &[member.pubkey().as_ref()]),
+        common::member_record(4, &[admin.pubkey().as_ref()]),
+        common::member_record(4, &[member.pubkey().as_ref()]),
+    ];
+    for record in records {
+        let result = common::apply_member_updates(
+            &mut svm,
+            &member,
+            &[&member],
+            multisig,
+            &[record],
+            vec![],
+        );
+        assert!(result.is_err());
+    }
+    assert_eq!(common::members(&svm, multisig.0), members);
+}
+
+#[test]
+fn test_execute_transaction_rotate_member_key() {
+    let (mut svm, second_admin, other_admin, multisig) = common::setup_two_admin_multisig();
+
+    // The rotated admin is a required approver for CPIs and voted on an open one
+    let update = UpdateMultisigIxData {
+        value: 1 << ProposalType::Cpi as u64,
+        update_type: 14, // 14 for update required approver
+        threshold: 0,
+    };
+    let result = common::apply_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        ProposalType::UpdateMultisig,
+        unsafe { to_bytes(&update) },
+        vec![AccountMeta::new_readonly(other_admin.pubkey(), false)], // required approver
+    );
+    assert!(result.is_ok());
+
+    let proposal = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        Pubkey::from(ID),
+        multisig.0,
+        ProposalType::Cpi,
+    );
+    let result = common::try_vote(&mut svm, &other_admin, multisig, proposal, 1);
+    assert!(result.is_ok());
+
+    let new_key = Keypair::new();
+    svm.airdrop(&new_key.pubkey(), 100000000).unwrap();
+    let rotate = common::member_record(
+        5,
+        &[other_admin.pubkey().as_ref(), new_key.pubkey().as_ref()],
+    );
+
+    // Every open proposal has to be passed along, this one was left out
+    let pending = common::proposal_pda(
+        Pubkey::from(ID),
+        multisig.0,
+        common::next_update_seed(&svm, multisig.0),
+    );
+    let result = common::apply_member_updates(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        &[rotate],
+        vec![],
+    );
+    assert!(result.is_err());
+
+    let result = common::apply_member_updates(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        &[rotate],
+        vec![
+            AccountMeta::new(proposal.0, false),
+            AccountMeta::new(pending.0, false),
+        ],
+    );
+    assert!(result.is_ok());
+
+    // The key changes in place, keeping the role, permissions and approver slot
+    let members = common::members(&svm, multisig.0);
+    assert_eq!(members[1].pubkey, new_key.pubkey().to_bytes());
+    assert_eq!(members[1].permissions, MemberState::ALL_PERMISSIONS);
+    let multisig_state = common::multisig_state(&svm, multisig.0);
+    assert_eq!(
+        multisig_state.required_approvers[0],
+        new_key.pubkey().to_bytes()
+    );
+
+    // The open proposal's vote moved along and can't be cast twice
+    let proposal_account = svm.get_account(&proposal.0).unwrap();
+    let votes = &proposal_account.data[ProposalState::LEN..ProposalState::LEN + 32];
+    assert_eq!(votes, new_key.pubkey().as_ref());
+    assert!(multisig_state.required_approvers_voted(ProposalType::Cpi, votes));
+
+    let result = common::try_vote(&mut svm, &new_key, multisig, proposal, 1);
+    assert!(result.is_err());
+    let result = common::try_vote(&mut svm, &other_admin, multisig, proposal, 0);
+    assert!(result.is_err());
+}
+
+/// Multisig with one admin and two normal members and a threshold of 1, returned with the
+/// admin and the members
+fn one_admin_two_member_multisig() -> (LiteSVM, Keypair, [Keypair; 2], (Pubkey, u8)) {
+    let (mut svm, fee_payer, admin, program_id) = common::setup_svm_and_program();
+    let members = [Keypair::new(), Keypair::new()];
+    let keys = [admin.pubkey(), members[0].pubkey(), members[1].pubkey()];
+    let multisig = common::init_multisig(&mut svm, &fee_payer, program_id, &keys, 1, 1);
+
+    (svm, admin, members, multisig)
+}
+
+#[test]
+fn test_execute_transaction_mixed_member_batch() {
+    let (mut svm, admin, [first, second], multisig) = one_admin_two_member_multisig();
+    let added = Keypair::new();
+
+    let records = [
+        common::member_record(1, &[added.pubkey().as_ref(), &[0, 0]]), // default permissions
+        common::member_record(2, &[second.pubkey().as_ref()]),
+        common::member_record(3, &[first.pubkey().as_ref()]),
+    ];
+    let result =
+        common::apply_member_updates(&mut svm, &admin, &[&admin], multisig, &records, vec![]);
+    assert!(result.is_ok());
+
+    let multisig_state = common::multisig_state(&svm, multisig.0);
+    assert_eq!(multisig_state.num_members, 3);
+    assert_eq!(multisig_state.admin_counter, 2);
+
+    let members = common::members(&svm, multisig.0);
+    let keys: Vec<_> = members.iter().map(|member| member.pubkey).collect();
+    assert_eq!(
+        keys,
+        [
+            admin.pubkey().to_bytes(),
+            first.pubkey().to_bytes(),
+            added.pubkey().to_bytes()
+        ]
+    );
+    assert_eq!(members[1].permissions, MemberState::ALL_PERMISSIONS);
+    assert_eq!(
+        members[2].permissions,
+        MemberState::DEFAULT_MEMBER_PERMISSIONS
+    );
+
+    // The account grew for the add and shrank back for the removal
+    let multisig_account = svm.get_account(&multisig.0).unwrap();
+    assert_eq!(
+        multisig_account.data.len(),
+        MultisigState::LEN + 3 * MemberState::LEN
+    );
+}
+
+#[test]
+fn test_execute_transaction_member_batch_rolls_back() {
+    let (mut svm, admin, [first, _], multisig) = one_admin_two_member_multisig();
+    let before = svm.get_account(&multisig.0).unwrap();
+    let added = Keypair::new();
+
+    // The records apply fine one by one, but the batch ends without an admin
+    let records = [
+        common::member_record(1, &[added.pubkey().as_ref(), &[0, 0]]),
+        common::member_record(3, &[first.pubkey().as_ref()]),
+        common::member_record(4, &[admin.pubkey().as_ref()]),
+        common::member_record(4, &[first.pubkey().as_ref()]),
+    ];
+    let result =
+        common::apply_member_updates(&mut svm, &admin, &[&admin], multisig, &records, vec![]);
+    assert!(result.is_err());
+
+    // Nothing from the batch sticks, not even the add that grew the account
+    let after = svm.get_account(&multisig.0).unwrap();
+    assert_eq!(
+        after.data[MultisigState::LEN..],
+        before.data[MultisigState::LEN..]
+    );
+    assert_eq!(common::multisig_state(&svm, multisig.0).admin_counter, 1);
+}
+
+#[test]
+fn test_execute_transaction_member_batch_malformed_record() {
+    let (mut svm, admin, _, multisig) = one_admin_two_member_multisig();
+    let members = common::members(&svm, multisig.0);
+    let added = Keypair::new();
+    let add = common::member_record(1, &[added.pubkey().as_ref(), &[0, 0]]);
+
+    // A truncated record after a valid one fails the whole batch
+    let truncated = [unsafe { to_bytes(&add) }, &[2u8; 10][..]].concat();
+    // So does a full record with an unknown operation
+    let unknown = [unsafe { to_bytes(&add) }, unsafe {
+        to_bytes(&common::member_record(9, &[]))
+    }]
+    .concat();
+
+    for data in [truncated, unknown] {
+        let result = common::apply_update(
+            &mut svm,
+            &admin,
+            &[&admin],
+            multisig,
+            ProposalType::UpdateMember,
+            &data,
+            vec![],
+        );
+        assert!(result.is_err());
+    }
+    assert_eq!(common::members(&svm, multisig.0), members);
+}
+
+/// Removes two members from a five member multisig whose threshold is 3, whose quorum is
+/// all five and whose reject threshold is 4, under `policy`. Returns whether the removal
+/// executed.
+fn remove_two_of_five(policy: RemovalPolicy) -> (bool, MultisigState) {
+    let (mut svm, fee_payer, admin, program_id) = common::setup_svm_and_program();
+    let members: Vec<Keypair> = (0..4).map(|_| Keypair::new()).collect();
+    for member in &members {
+        svm.airdrop(&member.pubkey(), 100000000).unwrap();
+    }
+    let keys: Vec<Pubkey> = std::iter::once(admin.pubkey())
+        .chain(members.iter().map(|member| member.pubkey()))
+        .collect();
+    let multisig = common::init_multisig(&mut svm, &fee_payer, program_id, &keys, 1, 1);
+    let voters: Vec<&Keypair> = std::iter::once(&admin).chain(members.iter()).collect();
+
+    for (update_type, value, threshold) in
+        [(4, policy as u64, 0), (1, 0, 3), (15, 0, 5), (10, 0, 4)]
+    {
+        let update = UpdateMultisigIxData {
+            value,
+            update_type,
+            threshold,
+        };
+        common::apply_multisig_update(&mut svm, &admin, &voters, multisig, update);
+    }
+
+    let records = [
+        common::member_record(2, &[members[2].pubkey().as_ref()]),
+        common::member_record(2, &[members[3].pubkey().as_ref()]),
+    ];
+    let result =
+        common::apply_member_updates(&mut svm, &admin, &voters, multisig, &records, vec![]);
+
+    (result.is_ok(), common::multisig_state(&svm, multisig.0))
+}
+
+#[test]
+fn test_execute_transaction_removal_policy_reject() {
+    let (removed, multisig_state) = remove_two_of_five(RemovalPolicy::Reject);
+
+    // A quorum of five can't be met by the three members left
+    assert!(!removed);
+    assert_eq!(multisig_state.num_members, 5);
+    assert_eq!(multisig_state.min_threshold, 3);
+    assert_eq!(multisig_state.quorum, 5);
+    assert_eq!(multisig_state.reject_threshold, 4);
+}
+
+#[test]
+fn test_execute_transaction_removal_policy_clamp() {
+    let (removed, multisig_state) = remove_two_of_five(RemovalPolicy::Clamp);
+
+    // The quorum and reject threshold drop to the remaining members, the threshold already fits
+    assert!(removed);
+    assert_eq!(multisig_state.num_members, 3);
+    assert_eq!(multisig_state.min_threshold, 3);
+    assert_eq!(multisig_state.quorum, 3);
+    assert_eq!(multisig_state.reject_threshold, 3);
+}
+
+#[test]
+fn test_execute_transaction_removal_policy_keep_ratio() {
+    let (removed, multisig_state) = remove_two_of_five(RemovalPolicy::KeepRatio);
+
+    // 3 of 5 scales to ceil(9 / 5) = 2 of 3, 5 of 5 to 3 of 3 and 4 of 5 to ceil(12 / 5) = 3
+    assert!(removed);
+    assert_eq!(multisig_state.num_members, 3);
+    assert_eq!(multisig_state.min_threshold, 2);
+    assert_eq!(multisig_state.quorum, 3);
+    assert_eq!(multisig_state.reject_threshold, 3);
+}
+
 #[test]
 fn test_execute_transaction_update_threshold() {
     let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
@@ -1004,7 +1428,7 @@ fn test_execute_transaction_update_threshold() {
     let multisig_seed = [(b"multisig"), &0u16.to_le_bytes() as &[u8]];
     let (pda_multisig, multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
 
-    let min_threshold: u8 = 0;
+    let min_threshold: u8 = 1;
     let max_expiry: u64 = 1_000_000;
     let num_members: u8 = 2;
     let primary_seed: u16 = 0;
@@ -1054,7 +1478,7 @@ fn test_execute_transaction_update_threshold() {
         pda_multisig.as_ref(),
         &proposal_primary_seed.to_le_bytes(),
     ];
-    let (pda_proposal, _proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
+    let (pda_proposal, proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
 
     let expiry: u64 = 3758794966; // Feb 09 2089
     let tx_type: u8 = 2; // UpdateMultisig
@@ -1073,7 +1497,7 @@ fn test_execute_transaction_update_threshold() {
         accounts: vec![
             AccountMeta::new(second_admin_pubkey, true), // creator (signer)
             AccountMeta::new(pda_proposal, false),       // proposal_account (will be created)
-            AccountMeta::new_readonly(pda_multisig, false), // multisig_account (readonly)
+            AccountMeta::new(pda_multisig, false),       // multisig_account
             AccountMeta::new_readonly(rent::ID, false),  // rent sysvar
             AccountMeta::new_readonly(clock::ID, false), // clock sysvar
             AccountMeta::new_readonly(system_program::id(), false), // system program
@@ -1093,7 +1517,7 @@ fn test_execute_transaction_update_threshold() {
     let update_multisig_data = UpdateMultisigIxData {
         value: 0,       // not used for threshold update
         update_type: 1, // 1 for update threshold
-        threshold: 3,   // new threshold value
+        threshold: 2,   // new threshold value
     };
 
     // Serialize the UpdateMultisig instruction data
@@ -1139,24 +1563,40 @@ fn test_execute_transaction_update_threshold() {
     assert!(result.is_ok());
     println!("✅ Transaction created with UpdateMultisig threshold instruction");
 
+    // Approve the proposal
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
     // Execute the transaction
     let execute_transaction_data = vec![5]; // discriminator for ExecuteTransaction instruction
 
     let execute_transaction_instruction = vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true), // payer (signer)
-            AccountMeta::new(pda_multisig, false),      // multisig
-            AccountMeta::new(pda_proposal, false),      // proposal
-            AccountMeta::new(pda_transaction, false),   // transaction
-            AccountMeta::new(rent::ID, false),          // rent
+            AccountMeta::new(second_admin.pubkey(), true), // payer (signer)
+            AccountMeta::new(pda_multisig, false),         // multisig
+            AccountMeta::new(pda_proposal, false),         // proposal
+            AccountMeta::new(pda_transaction, false),      // transaction
+            AccountMeta::new(rent::ID, false),             // rent
             AccountMeta::new(system_program::id(), false), // system program
+            AccountMeta::new(common::rent_collector(&svm, pda_multisig), false), // rent collector for the vote storage
         ],
         data: execute_transaction_data,
     }];
 
-    let result =
-        common::build_and_send_transaction(&mut svm, &fee_payer, execute_transaction_instruction);
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &second_admin,
+        execute_transaction_instruction,
+    );
     println!("Execute transaction result: {:?}", result);
     assert!(result.is_ok());
     println!("✅ Transaction executed successfully");
@@ -1167,7 +1607,7 @@ fn test_execute_transaction_update_threshold() {
     let multisig_state_bytes = &multisig_data[..MultisigState::LEN];
     let multisig_state: &MultisigState = bytemuck::from_bytes(multisig_state_bytes);
 
-    assert_eq!(multisig_state.min_threshold, 3); // Should be updated to 3
+    assert_eq!(multisig_state.min_threshold, 2); // Should be updated to 2
     println!("✅ Success: Threshold updated via execute transaction!");
 }
 
@@ -1183,7 +1623,7 @@ fn test_execute_transaction_update_spending_limit() {
     let multisig_seed = [(b"multisig"), &0u16.to_le_bytes() as &[u8]];
     let (pda_multisig, multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
 
-    let min_threshold: u8 = 0;
+    let min_threshold: u8 = 1;
     let max_expiry: u64 = 1_000_000;
     let num_members: u8 = 2;
     let primary_seed: u16 = 0;
@@ -1233,7 +1673,7 @@ fn test_execute_transaction_update_spending_limit() {
         pda_multisig.as_ref(),
         &proposal_primary_seed.to_le_bytes(),
     ];
-    let (pda_proposal, _proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
+    let (pda_proposal, proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
 
     let expiry: u64 = 3758794966; // Feb 09 2089
     let tx_type: u8 = 2; // UpdateMultisig
@@ -1252,7 +1692,7 @@ fn test_execute_transaction_update_spending_limit() {
         accounts: vec![
             AccountMeta::new(second_admin_pubkey, true), // creator (signer)
             AccountMeta::new(pda_proposal, false),       // proposal_account (will be created)
-            AccountMeta::new_readonly(pda_multisig, false), // multisig_account (readonly)
+            AccountMeta::new(pda_multisig, false),       // multisig_account
             AccountMeta::new_readonly(rent::ID, false),  // rent sysvar
             AccountMeta::new_readonly(clock::ID, false), // clock sysvar
             AccountMeta::new_readonly(system_program::id(), false), // system program
@@ -1318,24 +1758,40 @@ fn test_execute_transaction_update_spending_limit() {
     assert!(result.is_ok());
     println!("✅ Transaction created with UpdateMultisig spending limit instruction");
 
+    // Approve the proposal
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
     // Execute the transaction
     let execute_transaction_data = vec![5]; // discriminator for ExecuteTransaction instruction
 
     let execute_transaction_instruction = vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true), // payer (signer)
-            AccountMeta::new(pda_multisig, false),      // multisig
-            AccountMeta::new(pda_proposal, false),      // proposal
-            AccountMeta::new(pda_transaction, false),   // transaction
-            AccountMeta::new(rent::ID, false),          // rent
+            AccountMeta::new(second_admin.pubkey(), true), // payer (signer)
+            AccountMeta::new(pda_multisig, false),         // multisig
+            AccountMeta::new(pda_proposal, false),         // proposal
+            AccountMeta::new(pda_transaction, false),      // transaction
+            AccountMeta::new(rent::ID, false),             // rent
             AccountMeta::new(system_program::id(), false), // system program
+            AccountMeta::new(common::rent_collector(&svm, pda_multisig), false), // rent collector for the vote storage
         ],
         data: execute_transaction_data,
     }];
 
-    let result =
-        common::build_and_send_transaction(&mut svm, &fee_payer, execute_transaction_instruction);
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &second_admin,
+        execute_transaction_instruction,
+    );
     println!("Execute transaction result: {:?}", result);
     assert!(result.is_ok());
     println!("✅ Transaction executed successfully");
@@ -1362,7 +1818,7 @@ fn test_execute_transaction_update_stale_transaction_index() {
     let multisig_seed = [(b"multisig"), &0u16.to_le_bytes() as &[u8]];
     let (pda_multisig, multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
 
-    let min_threshold: u8 = 0;
+    let min_threshold: u8 = 1;
     let max_expiry: u64 = 1_000_000;
     let num_members: u8 = 2;
     let primary_seed: u16 = 0;
@@ -1412,7 +1868,7 @@ fn test_execute_transaction_update_stale_transaction_index() {
         pda_multisig.as_ref(),
         &proposal_primary_seed.to_le_bytes(),
     ];
-    let (pda_proposal, _proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
+    let (pda_proposal, proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
 
     let expiry: u64 = 3758794966; // Feb 09 2089
     let tx_type: u8 = 2; // UpdateMultisig
@@ -1431,7 +1887,7 @@ fn test_execute_transaction_update_stale_transaction_index() {
         accounts: vec![
             AccountMeta::new(second_admin_pubkey, true), // creator (signer)
             AccountMeta::new(pda_proposal, false),       // proposal_account (will be created)
-            AccountMeta::new_readonly(pda_multisig, false), // multisig_account (readonly)
+            AccountMeta::new(pda_multisig, false),       // multisig_account
             AccountMeta::new_readonly(rent::ID, false),  // rent sysvar
             AccountMeta::new_readonly(clock::ID, false), // clock sysvar
             AccountMeta::new_readonly(system_program::id(), false), // system program
@@ -1497,24 +1953,40 @@ fn test_execute_transaction_update_stale_transaction_index() {
     assert!(result.is_ok());
     println!("✅ Transaction created with UpdateMultisig stale transaction index instruction");
 
+    // Approve the proposal
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
     // Execute the transaction
     let execute_transaction_data = vec![5]; // discriminator for ExecuteTransaction instruction
 
     let execute_transaction_instruction = vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true), // payer (signer)
-            AccountMeta::new(pda_multisig, false),      // multisig
-            AccountMeta::new(pda_proposal, false),      // proposal
-            AccountMeta::new(pda_transaction, false),   // transaction
-            AccountMeta::new(rent::ID, false),          // rent
+            AccountMeta::new(second_admin.pubkey(), true), // payer (signer)
+            AccountMeta::new(pda_multisig, false),         // multisig
+            AccountMeta::new(pda_proposal, false),         // proposal
+            AccountMeta::new(pda_transaction, false),      // transaction
+            AccountMeta::new(rent::ID, false),             // rent
             AccountMeta::new(system_program::id(), false), // system program
+            AccountMeta::new(common::rent_collector(&svm, pda_multisig), false), // rent collector for the vote storage
         ],
         data: execute_transaction_data,
     }];
 
-    let result =
-        common::build_and_send_transaction(&mut svm, &fee_payer, execute_transaction_instruction);
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &second_admin,
+        execute_transaction_instruction,
+    );
     println!("Execute transaction result: {:?}", result);
     assert!(result.is_ok());
     println!("✅ Transaction executed successfully");
@@ -1530,67 +2002,1069 @@ fn test_execute_transaction_update_stale_transaction_index() {
 }
 
 #[test]
-fn test_execute_transaction_cpi_call() {
-    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
-    let fee_payer_pubkey = fee_payer.pubkey();
-    let second_admin_pubkey = second_admin.pubkey();
+fn test_execute_transaction_update_chamber_thresholds() {
+    let (mut svm, second_admin, _, multisig) = common::setup_two_admin_multisig();
 
-    let third_member = Keypair::new();
-    let fourth_member = Keypair::new();
-
-    let multisig_seed = [(b"multisig"), &0u16.to_le_bytes() as &[u8]];
-    let (pda_multisig, multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
+    let update = UpdateMultisigIxData {
+        value: 0,        // no regular member votes needed
+        update_type: 12, // 12 for update chamber thresholds
+        threshold: 2,    // both admins must vote yes
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
 
-    let min_threshold: u8 = 0;
-    let max_expiry: u64 = 1_000_000;
-    let num_members: u8 = 2;
-    let primary_seed: u16 = 0;
-    let num_admins: u8 = 2;
+    let multisig_state = common::multisig_state(&svm, multisig.0);
+    assert_eq!(multisig_state.admin_threshold, 2);
+    assert_eq!(multisig_state.member_threshold, 0);
 
-    let data = [
-        vec![0], // discriminator for InitMultisig instruction
-        max_expiry.to_le_bytes().to_vec(),
-        primary_seed.to_le_bytes().to_vec(),
-        min_threshold.to_le_bytes().to_vec(),
-        num_members.to_le_bytes().to_vec(),
-        num_admins.to_le_bytes().to_vec(),
-        vec![0; 3], // 3 bytes of padding for 8-byte alignment
-    ]
-    .concat();
+    // One admin's yes clears min_threshold but not the admin chamber
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        Pubkey::from(ID),
+        multisig.0,
+        ProposalType::Cpi,
+    );
+    let result = common::try_vote(
+        &mut svm,
+        &second_admin,
+        multisig,
+        (pda_proposal, proposal_bump),
+        1,
+    );
+    assert!(result.is_ok());
 
-    // Multisig Config PDA
-    let seed = [(b"multisig"), &primary_seed.to_le_bytes() as &[u8]];
-    let seeds = &seed[..];
-    let (pda_multisig, multisig_bump) = Pubkey::find_program_address(seeds, &program_id);
+    let proposal_state = common::proposal_state(&svm, pda_proposal);
+    assert_eq!(proposal_state.admin_yes_votes, 1);
+    assert_eq!(proposal_state.status, ProposalStatus::Active);
+    assert_eq!(proposal_state.approved_time, 0);
+}
 
-    // Treasury PDA
-    let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
-    let treasury_seeds = &treasury_seed[..];
-    let (pda_treasury, treasury_bump) = Pubkey::find_program_address(treasury_seeds, &program_id);
+#[test]
+fn test_execute_transaction_update_type_threshold() {
+    let (mut svm, second_admin, _, multisig) = common::setup_two_admin_multisig();
 
-    let instruction = vec![Instruction {
-        program_id: program_id,
-        accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true),
-            AccountMeta::new(pda_multisig, false),
-            AccountMeta::new(pda_treasury, false),
-            AccountMeta::new(rent::ID, false),
-            AccountMeta::new(system_program::id(), false),
-            AccountMeta::new(second_admin_pubkey, false),
-            AccountMeta::new(fourth_member.pubkey(), false),
-        ],
-        data,
-    }];
-    let multisig_result = common::build_and_send_transaction(&mut svm, &fee_payer, instruction);
-    assert!(multisig_result.is_ok(), "Failed to create multisig");
+    let update = UpdateMultisigIxData {
+        value: ProposalType::UpdateMultisig as u64,
+        update_type: 11, // 11 for update proposal type threshold
+        threshold: 2,    // config changes need both members
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
 
-    let target_program_pubkey = system_program::id();
+    let multisig_state = common::multisig_state(&svm, multisig.0);
+    assert_eq!(multisig_state.min_threshold, 1);
+    assert_eq!(
+        multisig_state.type_thresholds[ProposalType::UpdateMultisig as usize],
+        2
+    );
 
-    let source_account = Keypair::new();
-    let destination_account = Keypair::new();
+    // One yes vote no longer executes a config change
+    let update = UpdateMultisigIxData {
+        value: 0,
+        update_type: 10, // 10 for update reject threshold
+        threshold: 1,
+    };
+    let result = common::apply_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        ProposalType::UpdateMultisig,
+        unsafe { to_bytes(&update) },
+        vec![],
+    );
+    assert!(result.is_err());
+    assert_eq!(common::multisig_state(&svm, multisig.0).reject_threshold, 0);
 
-    svm.airdrop(&source_account.pubkey(), 1_000_000_000)
-        .unwrap();
+    // And a single no vote sinks it outright
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        Pubkey::from(ID),
+        multisig.0,
+        ProposalType::UpdateMultisig,
+    );
+    let result = common::try_vote(
+        &mut svm,
+        &second_admin,
+        multisig,
+        (pda_proposal, proposal_bump),
+        0,
+    );
+    assert!(result.is_ok());
+
+    let proposal_state = common::proposal_state(&svm, pda_proposal);
+    assert_eq!(proposal_state.status, ProposalStatus::Failed);
+}
+
+#[test]
+fn test_execute_transaction_update_transfer_tier() {
+    let (mut svm, second_admin, _, multisig) = common::setup_two_admin_multisig();
+
+    let update = UpdateMultisigIxData {
+        value: 1_000_000_000, // transfers below 1 SOL
+        update_type: 13,      // 13 for update transfer tier
+        threshold: 2,         // need both members
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+
+    let multisig_state = common::multisig_state(&svm, multisig.0);
+    assert_eq!(multisig_state.min_threshold, 1);
+    assert_eq!(multisig_state.transfer_tier_limits[0], 1_000_000_000);
+    assert_eq!(multisig_state.transfer_tier_thresholds[0], 2);
+    assert_eq!(multisig_state.transfer_tier_thresholds[1..], [0; 3]);
+
+    // Small transfers take the tier's threshold, larger ones stay at least as strict
+    assert_eq!(multisig_state.threshold_for_transfer(500_000_000), 2);
+    assert_eq!(multisig_state.threshold_for_transfer(2_000_000_000), 2);
+}
+
+#[test]
+fn test_execute_transaction_transfer_tier_votes() {
+    let (mut svm, second_admin, other_admin, multisig) = common::setup_two_admin_multisig();
+
+    // Transfers below 1 SOL need both admins, everything else just one
+    let update = UpdateMultisigIxData {
+        value: 1_000_000_000,
+        update_type: 13, // 13 for update transfer tier
+        threshold: 2,
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+
+    let source_account = Keypair::new();
+    let destination_account = Keypair::new();
+    svm.airdrop(&source_account.pubkey(), 1_000_000_000)
+        .unwrap();
+    svm.airdrop(&destination_account.pubkey(), 1_000_000)
+        .unwrap();
+    let transfer_accounts = vec![
+        AccountMeta::new(source_account.pubkey(), true),
+        AccountMeta::new(destination_account.pubkey(), false),
+    ];
+
+    // SystemInstruction::Transfer of 0.05 SOL
+    let transfer_data = [
+        2u32.to_le_bytes().to_vec(),
+        50_000_000u64.to_le_bytes().to_vec(),
+    ]
+    .concat();
+    let transaction_index = common::multisig_state(&svm, multisig.0).transaction_index;
+    let pda_transaction = common::create_transaction(
+        &mut svm,
+        &second_admin,
+        40,
+        transaction_index,
+        system_program::id(),
+        &transfer_data,
+    );
+
+    let rent_collector = common::rent_collector(&svm, multisig.0);
+    let execute_ix = |proposal: Pubkey| Instruction {
+        program_id: Pubkey::from(ID),
+        accounts: [
+            vec![
+                AccountMeta::new(second_admin.pubkey(), true),
+                AccountMeta::new(multisig.0, false),
+                AccountMeta::new(proposal, false),
+                AccountMeta::new(pda_transaction, false),
+                AccountMeta::new_readonly(rent::ID, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(rent_collector, false),
+            ],
+            transfer_accounts.clone(),
+        ]
+        .concat(),
+        data: vec![5],
+    };
+
+    // A proposal that isn't bound to the transfer can't tell its tier while voting
+    let unbound = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        Pubkey::from(ID),
+        multisig.0,
+        ProposalType::Cpi,
+    );
+    let result = common::try_vote(&mut svm, &second_admin, multisig, unbound, 1);
+    assert!(result.is_ok());
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &second_admin,
+        vec![execute_ix(unbound.0)],
+        &[&source_account],
+    );
+    assert!(result.is_err());
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = 1_000;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let create_proposal_data = CreateProposalIxData {
+        expiry: 1_000_000,
+        primary_seed: 1,
+        tx_type: ProposalType::Cpi,
+        optimistic: 0,
+        options: 1,
+    };
+    let result = common::try_create_proposal_with(
+        &mut svm,
+        &second_admin,
+        multisig.0,
+        create_proposal_data,
+        &[pda_transaction],
+    );
+    assert!(result.is_ok());
+    let bound = common::proposal_pda(Pubkey::from(ID), multisig.0, 1);
+    assert_eq!(
+        common::proposal_state(&svm, bound.0).transfer_amount(0),
+        Some(50_000_000)
+    );
+
+    // One yes vote clears the CPI threshold but not the tier, so the clock doesn't start
+    let result = common::try_vote(&mut svm, &second_admin, multisig, bound, 1);
+    assert!(result.is_ok());
+    assert_eq!(common::proposal_state(&svm, bound.0).approved_time, 0);
+
+    svm.expire_blockhash();
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &second_admin,
+        vec![execute_ix(bound.0)],
+        &[&source_account],
+    );
+    assert!(result.is_err());
+
+    let result = common::try_vote(&mut svm, &other_admin, multisig, bound, 1);
+    assert!(result.is_ok());
+    assert_eq!(common::proposal_state(&svm, bound.0).approved_time, 1_000);
+
+    svm.expire_blockhash();
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &second_admin,
+        vec![execute_ix(bound.0)],
+        &[&source_account],
+    );
+    assert!(result.is_ok());
+    assert_eq!(
+        svm.get_account(&destination_account.pubkey())
+            .unwrap()
+            .lamports,
+        51_000_000
+    );
+}
+
+/// 0.05 SOL system transfer stored at `primary_seed` for the multisig's next transaction,
+/// returned with the funded source that has to sign its execution and the accounts it needs
+fn transfer_transaction(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    multisig: Pubkey,
+    primary_seed: u16,
+) -> (Pubkey, Keypair, Vec<AccountMeta>) {
+    let source_account = Keypair::new();
+    let destination_account = Keypair::new();
+    svm.airdrop(&source_account.pubkey(), 1_000_000_000)
+        .unwrap();
+    svm.airdrop(&destination_account.pubkey(), 1_000_000)
+        .unwrap();
+
+    // SystemInstruction::Transfer
+    let transfer_data = [
+        2u32.to_le_bytes().to_vec(),
+        50_000_000u64.to_le_bytes().to_vec(),
+    ]
+    .concat();
+    let transaction_index = common::multisig_state(svm, multisig).transaction_index;
+    let transaction = common::create_transaction(
+        svm,
+        payer,
+        primary_seed,
+        transaction_index,
+        system_program::id(),
+        &transfer_data,
+    );
+
+    let transfer_accounts = vec![
+        AccountMeta::new(source_account.pubkey(), true),
+        AccountMeta::new(destination_account.pubkey(), false),
+    ];
+    (transaction, source_account, transfer_accounts)
+}
+
+fn try_execute_transfer(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    multisig: Pubkey,
+    proposal: Pubkey,
+    transfer: &(Pubkey, Keypair, Vec<AccountMeta>),
+) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+    let (transaction, source_account, transfer_accounts) = transfer;
+    let rent_collector = common::rent_collector(svm, multisig);
+    let execute_ix = Instruction {
+        program_id: Pubkey::from(ID),
+        accounts: [
+            vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(multisig, false),
+                AccountMeta::new(proposal, false),
+                AccountMeta::new(*transaction, false),
+                AccountMeta::new_readonly(rent::ID, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(rent_collector, false),
+            ],
+            transfer_accounts.clone(),
+        ]
+        .concat(),
+        data: vec![5],
+    };
+
+    svm.expire_blockhash();
+    common::build_and_send_transaction_multisig(svm, payer, vec![execute_ix], &[source_account])
+}
+
+/// Enables optimistic proposals with a 100 second challenge window that one no vote vetoes
+fn enable_optimistic_proposals(svm: &mut LiteSVM, admin: &Keypair, multisig: (Pubkey, u8)) {
+    let challenge_window = UpdateMultisigIxData {
+        value: 100,
+        update_type: 17, // 17 for update challenge window
+        threshold: 0,
+    };
+    common::apply_multisig_update(svm, admin, &[admin], multisig, challenge_window);
+    let reject_threshold = UpdateMultisigIxData {
+        value: 0,
+        update_type: 10, // 10 for update reject threshold
+        threshold: 1,
+    };
+    common::apply_multisig_update(svm, admin, &[admin], multisig, reject_threshold);
+}
+
+fn set_unix_timestamp(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar::<Clock>(&clock);
+}
+
+const OPTIMISTIC_CPI: CreateProposalIxData = CreateProposalIxData {
+    expiry: 1_000_000,
+    primary_seed: 1,
+    tx_type: ProposalType::Cpi,
+    optimistic: 1,
+    options: 1,
+};
+
+#[test]
+fn test_execute_transaction_releases_vote_storage() {
+    let (mut svm, second_admin, other_admin, multisig) = common::setup_two_admin_multisig();
+
+    let transfer = transfer_transaction(&mut svm, &second_admin, multisig.0, 40);
+    let result =
+        common::try_create_proposal(&mut svm, &second_admin, multisig.0, 1, ProposalType::Cpi);
+    assert!(result.is_ok());
+    let proposal = common::proposal_pda(Pubkey::from(ID), multisig.0, 1);
+    for admin in [&second_admin, &other_admin] {
+        let result = common::try_vote(&mut svm, admin, multisig, proposal, 1);
+        assert!(result.is_ok());
+    }
+    let storage_len = ProposalState::LEN + 2 * 32;
+    assert_eq!(
+        svm.get_account(&proposal.0).unwrap().data.len(),
+        storage_len
+    );
+
+    // The vote storage only goes to the multisig's own rent collector
+    let (transaction, source_account, transfer_accounts) = &transfer;
+    let execute_ix = Instruction {
+        program_id: Pubkey::from(ID),
+        accounts: [
+            vec![
+                AccountMeta::new(second_admin.pubkey(), true),
+                AccountMeta::new(multisig.0, false),
+                AccountMeta::new(proposal.0, false),
+                AccountMeta::new(*transaction, false),
+                AccountMeta::new_readonly(rent::ID, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(Pubkey::new_unique(), false),
+            ],
+            transfer_accounts.clone(),
+        ]
+        .concat(),
+        data: vec![5],
+    };
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &second_admin,
+        vec![execute_ix],
+        &[source_account],
+    );
+    assert!(result.is_err());
+    assert_eq!(
+        common::proposal_state(&svm, proposal.0).status,
+        ProposalStatus::Active
+    );
+
+    let rent_collector = common::rent_collector(&svm, multisig.0);
+    let collector_lamports_before = svm.get_account(&rent_collector).unwrap().lamports;
+
+    let result = try_execute_transfer(&mut svm, &second_admin, multisig.0, proposal.0, &transfer);
+    assert!(result.is_ok());
+
+    // Only the header of the executed proposal is left, its storage rent went to the collector
+    assert_eq!(
+        svm.get_account(&proposal.0).unwrap().data.len(),
+        ProposalState::LEN
+    );
+    let freed = svm.minimum_balance_for_rent_exemption(storage_len)
+        - svm.minimum_balance_for_rent_exemption(ProposalState::LEN);
+    assert_eq!(
+        svm.get_account(&rent_collector).unwrap().lamports,
+        collector_lamports_before + freed
+    );
+
+    // And it stays closed to votes
+    let result = common::try_vote(&mut svm, &other_admin, multisig, proposal, 0);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_execute_transaction_time_lock() {
+    let (mut svm, second_admin, other_admin, multisig) = common::setup_two_admin_multisig();
+
+    let update = UpdateMultisigIxData {
+        value: 50,
+        update_type: 7, // 7 for update time lock
+        threshold: 0,
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+
+    // A timelock as long as the max expiry would leave no proposal time to execute
+    let lock_out = UpdateMultisigIxData {
+        value: 1_000_000,
+        update_type: 7, // 7 for update time lock
+        threshold: 0,
+    };
+    let result = common::apply_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        ProposalType::UpdateMultisig,
+        unsafe { to_bytes(&lock_out) },
+        vec![],
+    );
+    assert!(result.is_err());
+    assert_eq!(common::multisig_state(&svm, multisig.0).time_lock, 50);
+
+    set_unix_timestamp(&mut svm, 1_000);
+
+    // Nor can a proposal's deadline come before its timelock could end
+    let short_deadline = CreateProposalIxData {
+        expiry: 49,
+        primary_seed: 3,
+        tx_type: ProposalType::Cpi,
+        optimistic: 0,
+        options: 0,
+    };
+    let result =
+        common::try_create_proposal_with(&mut svm, &second_admin, multisig.0, short_deadline, &[]);
+    assert!(result.is_err());
+
+    let transfer = transfer_transaction(&mut svm, &second_admin, multisig.0, 40);
+    let result =
+        common::try_create_proposal(&mut svm, &second_admin, multisig.0, 1, ProposalType::Cpi);
+    assert!(result.is_ok());
+    let proposal = common::proposal_pda(Pubkey::from(ID), multisig.0, 1);
+    let result = common::try_vote(&mut svm, &second_admin, multisig, proposal, 1);
+    assert!(result.is_ok());
+    assert_eq!(
+        common::proposal_state(&svm, proposal.0).approved_time,
+        1_000
+    );
+
+    // Approved, but still inside the timelock
+    set_unix_timestamp(&mut svm, 1_049);
+    let result = try_execute_transfer(&mut svm, &second_admin, multisig.0, proposal.0, &transfer);
+    assert!(result.is_err());
+
+    set_unix_timestamp(&mut svm, 1_050);
+    let result = try_execute_transfer(&mut svm, &second_admin, multisig.0, proposal.0, &transfer);
+    assert!(result.is_ok());
+
+    // A proposal cancelled during its timelock stays unexecutable once the lock is over
+    let transfer = transfer_transaction(&mut svm, &second_admin, multisig.0, 41);
+    let result =
+        common::try_create_proposal(&mut svm, &second_admin, multisig.0, 2, ProposalType::Cpi);
+    assert!(result.is_ok());
+    let proposal = common::proposal_pda(Pubkey::from(ID), multisig.0, 2);
+    let result = common::try_vote(&mut svm, &second_admin, multisig, proposal, 1);
+    assert!(result.is_ok());
+
+    set_unix_timestamp(&mut svm, 1_070);
+    let cancel_ix = Instruction {
+        program_id: Pubkey::from(ID),
+        accounts: vec![
+            AccountMeta::new(other_admin.pubkey(), true),
+            AccountMeta::new(multisig.0, false),
+            AccountMeta::new(proposal.0, false),
+            AccountMeta::new(common::rent_collector(&svm, multisig.0), false),
+        ],
+        data: vec![8, multisig.1], // discriminator for CancelProposal instruction
+    };
+    let result = common::build_and_send_transaction(&mut svm, &other_admin, vec![cancel_ix]);
+    assert!(result.is_ok());
+
+    set_unix_timestamp(&mut svm, 1_100);
+    let result = try_execute_transfer(&mut svm, &second_admin, multisig.0, proposal.0, &transfer);
+    assert!(result.is_err());
+    assert_eq!(
+        common::proposal_state(&svm, proposal.0).status,
+        ProposalStatus::Cancelled
+    );
+}
+
+#[test]
+fn test_execute_optimistic_proposal() {
+    let (mut svm, second_admin, other_admin, multisig) = common::setup_two_admin_multisig();
+    enable_optimistic_proposals(&mut svm, &second_admin, multisig);
+
+    // CPIs need the other admin's yes vote, optimistic or not
+    let update = UpdateMultisigIxData {
+        value: 1 << ProposalType::Cpi as u64,
+        update_type: 14, // 14 for update required approver
+        threshold: 0,
+    };
+    let result = common::apply_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        ProposalType::UpdateMultisig,
+        unsafe { to_bytes(&update) },
+        vec![AccountMeta::new_readonly(other_admin.pubkey(), false)], // required approver
+    );
+    assert!(result.is_ok());
+
+    set_unix_timestamp(&mut svm, 1_000);
+    let transfer = transfer_transaction(&mut svm, &second_admin, multisig.0, 40);
+
+    // Unbound, it would run whatever transaction is next once the window closes
+    let unbound = CreateProposalIxData {
+        options: 0,
+        ..OPTIMISTIC_CPI
+    };
+    let result =
+        common::try_create_proposal_with(&mut svm, &second_admin, multisig.0, unbound, &[]);
+    assert!(result.is_err());
+
+    let result = common::try_create_proposal_with(
+        &mut svm,
+        &second_admin,
+        multisig.0,
+        OPTIMISTIC_CPI,
+        &[transfer.0],
+    );
+    assert!(result.is_ok());
+    let proposal = common::proposal_pda(Pubkey::from(ID), multisig.0, 1);
+    assert_eq!(common::multisig_state(&svm, multisig.0).open_optimistic, 1);
+
+    // The veto can't be switched off under an open optimistic proposal
+    let disable_veto = UpdateMultisigIxData {
+        value: 0,
+        update_type: 10, // 10 for update reject threshold
+        threshold: 0,
+    };
+    let result = common::apply_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        ProposalType::UpdateMultisig,
+        unsafe { to_bytes(&disable_veto) },
+        vec![],
+    );
+    assert!(result.is_err());
+
+    // Still inside the challenge window
+    set_unix_timestamp(&mut svm, 1_050);
+    let result = try_execute_transfer(&mut svm, &second_admin, multisig.0, proposal.0, &transfer);
+    assert!(result.is_err());
+
+    // Past it, but the required approver hasn't voted yes
+    set_unix_timestamp(&mut svm, 1_100);
+    let result = try_execute_transfer(&mut svm, &second_admin, multisig.0, proposal.0, &transfer);
+    assert!(result.is_err());
+
+    let result = common::try_vote(&mut svm, &other_admin, multisig, proposal, 1);
+    assert!(result.is_ok());
+    let result = try_execute_transfer(&mut svm, &second_admin, multisig.0, proposal.0, &transfer);
+    assert!(result.is_ok());
+    assert_eq!(
+        common::proposal_state(&svm, proposal.0).status,
+        ProposalStatus::Succeeded
+    );
+    assert_eq!(common::multisig_state(&svm, multisig.0).open_optimistic, 0);
+
+    let result = common::apply_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        ProposalType::UpdateMultisig,
+        unsafe { to_bytes(&disable_veto) },
+        vec![],
+    );
+    assert!(result.is_ok());
+    assert_eq!(common::multisig_state(&svm, multisig.0).reject_threshold, 0);
+}
+
+#[test]
+fn test_execute_optimistic_proposal_time_lock() {
+    let (mut svm, second_admin, _, multisig) = common::setup_two_admin_multisig();
+    enable_optimistic_proposals(&mut svm, &second_admin, multisig);
+
+    let update = UpdateMultisigIxData {
+        value: 50,
+        update_type: 7, // 7 for update time lock
+        threshold: 0,
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+
+    set_unix_timestamp(&mut svm, 1_000);
+    let transfer = transfer_transaction(&mut svm, &second_admin, multisig.0, 40);
+    let result = common::try_create_proposal_with(
+        &mut svm,
+        &second_admin,
+        multisig.0,
+        OPTIMISTIC_CPI,
+        &[transfer.0],
+    );
+    assert!(result.is_ok());
+    let proposal = common::proposal_pda(Pubkey::from(ID), multisig.0, 1);
+
+    // The timelock only starts once the challenge window is over
+    set_unix_timestamp(&mut svm, 1_100);
+    let result = try_execute_transfer(&mut svm, &second_admin, multisig.0, proposal.0, &transfer);
+    assert!(result.is_err());
+
+    set_unix_timestamp(&mut svm, 1_150);
+    let result = try_execute_transfer(&mut svm, &second_admin, multisig.0, proposal.0, &transfer);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_execute_optimistic_transfer_with_tiers() {
+    let (mut svm, second_admin, other_admin, multisig) = common::setup_two_admin_multisig();
+    enable_optimistic_proposals(&mut svm, &second_admin, multisig);
+
+    let update = UpdateMultisigIxData {
+        value: 1_000_000_000,
+        update_type: 13, // 13 for update transfer tier
+        threshold: 2,
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+
+    set_unix_timestamp(&mut svm, 1_000);
+    let transfer = transfer_transaction(&mut svm, &second_admin, multisig.0, 40);
+    let result = common::try_create_proposal_with(
+        &mut svm,
+        &second_admin,
+        multisig.0,
+        OPTIMISTIC_CPI,
+        &[transfer.0],
+    );
+    assert!(result.is_ok());
+    let proposal = common::proposal_pda(Pubkey::from(ID), multisig.0, 1);
+
+    // Nothing counted the tier's votes, so the transfer can't pass on time alone
+    set_unix_timestamp(&mut svm, 1_100);
+    let result = try_execute_transfer(&mut svm, &second_admin, multisig.0, proposal.0, &transfer);
+    assert!(result.is_err());
+
+    // A veto still closes it out
+    let result = common::try_vote(&mut svm, &other_admin, multisig, proposal, 0);
+    assert!(result.is_ok());
+    assert_eq!(
+        common::proposal_state(&svm, proposal.0).status,
+        ProposalStatus::Failed
+    );
+    assert_eq!(common::multisig_state(&svm, multisig.0).open_optimistic, 0);
+}
+
+#[test]
+fn test_execute_transaction_update_required_approver() {
+    let (mut svm, second_admin, other_admin, multisig) = common::setup_two_admin_multisig();
+
+    let update = UpdateMultisigIxData {
+        value: 1 << ProposalType::UpdateMultisig as u64, // config changes only
+        update_type: 14,                                 // 14 for update required approver
+        threshold: 0,
+    };
+    let result = common::apply_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        ProposalType::UpdateMultisig,
+        unsafe { to_bytes(&update) },
+        vec![AccountMeta::new_readonly(other_admin.pubkey(), false)], // required approver
+    );
+    assert!(result.is_ok());
+
+    let multisig_state = common::multisig_state(&svm, multisig.0);
+    assert_eq!(
+        multisig_state.required_approvers[0],
+        other_admin.pubkey().to_bytes()
+    );
+    assert_eq!(
+        multisig_state.required_approver_types[0],
+        1 << ProposalType::UpdateMultisig as u8
+    );
+
+    // Config changes now need the other admin's yes vote, other proposals don't
+    let second_admin_vote = second_admin.pubkey().to_bytes();
+    assert!(!multisig_state
+        .required_approvers_voted(ProposalType::UpdateMultisig, second_admin_vote.as_ref()));
+    assert!(multisig_state.required_approvers_voted(ProposalType::Cpi, second_admin_vote.as_ref()));
+    let both_votes = [second_admin_vote, other_admin.pubkey().to_bytes()].concat();
+    assert!(multisig_state.required_approvers_voted(ProposalType::UpdateMultisig, &both_votes));
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = 1_000;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let proposal = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        Pubkey::from(ID),
+        multisig.0,
+        ProposalType::UpdateMultisig,
+    );
+
+    // The threshold alone doesn't start the approval clock without the required approver
+    let result = common::try_vote(&mut svm, &second_admin, multisig, proposal, 1);
+    assert!(result.is_ok());
+    assert_eq!(common::proposal_state(&svm, proposal.0).approved_time, 0);
+
+    let result = common::try_vote(&mut svm, &other_admin, multisig, proposal, 1);
+    assert!(result.is_ok());
+    assert_eq!(
+        common::proposal_state(&svm, proposal.0).approved_time,
+        1_000
+    );
+}
+
+#[test]
+fn test_execute_transaction_update_reject_threshold() {
+    let (mut svm, second_admin, _, multisig) = common::setup_two_admin_multisig();
+
+    let update = UpdateMultisigIxData {
+        value: 0,        // not used for reject threshold update
+        update_type: 10, // 10 for update reject threshold
+        threshold: 1,    // a single no vote fails a proposal
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+    assert_eq!(common::multisig_state(&svm, multisig.0).reject_threshold, 1);
+
+    // Above the two members who can vote, no veto could ever land
+    let unreachable = UpdateMultisigIxData {
+        value: 0,
+        update_type: 10, // 10 for update reject threshold
+        threshold: 3,
+    };
+    let result = common::apply_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        ProposalType::UpdateMultisig,
+        unsafe { to_bytes(&unreachable) },
+        vec![],
+    );
+    assert!(result.is_err());
+    assert_eq!(common::multisig_state(&svm, multisig.0).reject_threshold, 1);
+
+    // One no vote now fails a proposal that could otherwise still pass
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        Pubkey::from(ID),
+        multisig.0,
+        ProposalType::Cpi,
+    );
+    let result = common::try_vote(
+        &mut svm,
+        &second_admin,
+        multisig,
+        (pda_proposal, proposal_bump),
+        0,
+    );
+    assert!(result.is_ok());
+
+    let proposal_state = common::proposal_state(&svm, pda_proposal);
+    assert_eq!(proposal_state.status, ProposalStatus::Failed);
+}
+
+#[test]
+fn test_execute_transaction_update_challenge_window() {
+    let (mut svm, second_admin, _, multisig) = common::setup_two_admin_multisig();
+
+    let update = UpdateMultisigIxData {
+        value: 86_400,   // one day to veto
+        update_type: 17, // 17 for update challenge window
+        threshold: 0,    // not used for challenge window update
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+    assert_eq!(
+        common::multisig_state(&svm, multisig.0).challenge_window,
+        86_400
+    );
+}
+
+#[test]
+fn test_execute_transaction_update_threshold_percent() {
+    let (mut svm, second_admin, _, multisig) = common::setup_two_admin_multisig();
+
+    let update = UpdateMultisigIxData {
+        value: 0,        // not used for threshold percentage update
+        update_type: 16, // 16 for update threshold percentage
+        threshold: 51,   // a majority of the members
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+
+    let multisig_state = common::multisig_state(&svm, multisig.0);
+    assert_eq!(multisig_state.threshold_percent, 51);
+    assert_eq!(multisig_state.min_threshold, 1);
+
+    // 51% of 2 members rounds up to both of them
+    assert_eq!(multisig_state.base_threshold(), 2);
+    assert_eq!(multisig_state.threshold_for(ProposalType::Cpi), 2);
+
+    // A single no vote now leaves too few members to reach the threshold
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        Pubkey::from(ID),
+        multisig.0,
+        ProposalType::Cpi,
+    );
+    let result = common::try_vote(
+        &mut svm,
+        &second_admin,
+        multisig,
+        (pda_proposal, proposal_bump),
+        0,
+    );
+    assert!(result.is_ok());
+
+    let proposal_state = common::proposal_state(&svm, pda_proposal);
+    assert_eq!(proposal_state.status, ProposalStatus::Failed);
+}
+
+#[test]
+fn test_execute_transaction_update_quorum() {
+    let (mut svm, second_admin, other_admin, multisig) = common::setup_two_admin_multisig();
+
+    let update = UpdateMultisigIxData {
+        value: 0,        // not used for quorum update
+        update_type: 15, // 15 for update quorum
+        threshold: 2,    // both members must take part
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+    assert_eq!(common::multisig_state(&svm, multisig.0).quorum, 2);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = 1_000;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let proposal = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        Pubkey::from(ID),
+        multisig.0,
+        ProposalType::Cpi,
+    );
+
+    // One yes vote meets the threshold but not the quorum
+    let result = common::try_vote(&mut svm, &second_admin, multisig, proposal, 1);
+    assert!(result.is_ok());
+    assert_eq!(common::proposal_state(&svm, proposal.0).approved_time, 0);
+
+    // An abstention completes the quorum
+    let result = common::try_vote(&mut svm, &other_admin, multisig, proposal, 2);
+    assert!(result.is_ok());
+
+    let proposal_state = common::proposal_state(&svm, proposal.0);
+    assert_eq!(proposal_state.abstain_votes, 1);
+    assert_eq!(proposal_state.approved_time, 1_000);
+}
+
+#[test]
+fn test_execute_transaction_update_time_unit() {
+    let (mut svm, second_admin, other_admin, multisig) = common::setup_two_admin_multisig();
+
+    // A one day challenge window and a 100 second invitation TTL
+    for (update_type, value) in [(17, 86_400), (5, 100)] {
+        let update = UpdateMultisigIxData {
+            value,
+            update_type,
+            threshold: 0,
+        };
+        common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+    }
+
+    let update = UpdateMultisigIxData {
+        value: 1,       // TimeUnit::Slot
+        update_type: 8, // 8 for update time unit
+        threshold: 0,   // not used for time unit update
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+
+    // The durations are carried over in 400ms slots
+    let multisig_state = common::multisig_state(&svm, multisig.0);
+    assert_eq!(multisig_state.time_unit, TimeUnit::Slot as u8);
+    assert_eq!(multisig_state.max_expiry, 2_500_000);
+    assert_eq!(multisig_state.challenge_window, 216_000);
+    assert_eq!(multisig_state.invitation_ttl, 250);
+
+    // New proposals take their deadline from the slot
+    svm.warp_to_slot(500);
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        Pubkey::from(ID),
+        multisig.0,
+        ProposalType::Cpi,
+    );
+
+    let proposal_state = common::proposal_state(&svm, pda_proposal);
+    assert_eq!(proposal_state.created_time, 500);
+    assert_eq!(proposal_state.expiry, 500 + 1_000_000);
+
+    // Switching back would reread that deadline as a timestamp
+    let update = UpdateMultisigIxData {
+        value: 0,       // TimeUnit::UnixTimestamp
+        update_type: 8, // 8 for update time unit
+        threshold: 0,
+    };
+    let update_seed = common::next_update_seed(&svm, multisig.0);
+    let result = common::apply_update(
+        &mut svm,
+        &second_admin,
+        &[&second_admin],
+        multisig,
+        ProposalType::UpdateMultisig,
+        unsafe { to_bytes(&update) },
+        vec![],
+    );
+    assert!(result.is_err());
+    assert_eq!(common::multisig_state(&svm, multisig.0).open_proposals, 2);
+
+    // Once the other proposal is closed the update itself is the only open one
+    let result = common::try_vote(
+        &mut svm,
+        &second_admin,
+        multisig,
+        (pda_proposal, proposal_bump),
+        0,
+    );
+    assert!(result.is_ok());
+    let result = common::try_vote(
+        &mut svm,
+        &other_admin,
+        multisig,
+        (pda_proposal, proposal_bump),
+        0,
+    );
+    assert!(result.is_ok());
+    assert_eq!(
+        common::proposal_state(&svm, pda_proposal).status,
+        ProposalStatus::Failed
+    );
+
+    let update_proposal = common::proposal_pda(Pubkey::from(ID), multisig.0, update_seed);
+    let (update_transaction, _) = Pubkey::find_program_address(
+        &[b"transaction", &update_seed.to_le_bytes()],
+        &Pubkey::from(ID),
+    );
+    let result = common::execute_transaction(
+        &mut svm,
+        &second_admin,
+        multisig.0,
+        update_proposal.0,
+        update_transaction,
+        vec![],
+    );
+    assert!(result.is_ok());
+
+    let multisig_state = common::multisig_state(&svm, multisig.0);
+    assert_eq!(multisig_state.time_unit, TimeUnit::UnixTimestamp as u8);
+    assert_eq!(multisig_state.open_proposals, 0);
+    assert_eq!(multisig_state.max_expiry, 1_000_000);
+    assert_eq!(multisig_state.challenge_window, 86_400);
+    assert_eq!(multisig_state.invitation_ttl, 100);
+}
+
+#[test]
+fn test_execute_transaction_cpi_call() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+    let fee_payer_pubkey = fee_payer.pubkey();
+    let second_admin_pubkey = second_admin.pubkey();
+
+    let third_member = Keypair::new();
+    let fourth_member = Keypair::new();
+
+    let multisig_seed = [(b"multisig"), &0u16.to_le_bytes() as &[u8]];
+    let (pda_multisig, multisig_bump) = Pubkey::find_program_address(&multisig_seed, &program_id);
+
+    let min_threshold: u8 = 1;
+    let max_expiry: u64 = 1_000_000;
+    let num_members: u8 = 2;
+    let primary_seed: u16 = 0;
+    let num_admins: u8 = 2;
+
+    let data = [
+        vec![0], // discriminator for InitMultisig instruction
+        max_expiry.to_le_bytes().to_vec(),
+        primary_seed.to_le_bytes().to_vec(),
+        min_threshold.to_le_bytes().to_vec(),
+        num_members.to_le_bytes().to_vec(),
+        num_admins.to_le_bytes().to_vec(),
+        vec![0; 3], // 3 bytes of padding for 8-byte alignment
+    ]
+    .concat();
+
+    // Multisig Config PDA
+    let seed = [(b"multisig"), &primary_seed.to_le_bytes() as &[u8]];
+    let seeds = &seed[..];
+    let (pda_multisig, multisig_bump) = Pubkey::find_program_address(seeds, &program_id);
+
+    // Treasury PDA
+    let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
+    let treasury_seeds = &treasury_seed[..];
+    let (pda_treasury, treasury_bump) = Pubkey::find_program_address(treasury_seeds, &program_id);
+
+    let instruction = vec![Instruction {
+        program_id: program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_treasury, false),
+            AccountMeta::new(rent::ID, false),
+            AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(second_admin_pubkey, false),
+            AccountMeta::new(fourth_member.pubkey(), false),
+        ],
+        data,
+    }];
+    let multisig_result = common::build_and_send_transaction(&mut svm, &fee_payer, instruction);
+    assert!(multisig_result.is_ok(), "Failed to create multisig");
+
+    let target_program_pubkey = system_program::id();
+
+    let source_account = Keypair::new();
+    let destination_account = Keypair::new();
+
+    svm.airdrop(&source_account.pubkey(), 1_000_000_000)
+        .unwrap();
     svm.airdrop(&destination_account.pubkey(), 100_000_000)
         .unwrap();
 
@@ -1601,7 +3075,7 @@ fn test_execute_transaction_cpi_call() {
         pda_multisig.as_ref(),
         &proposal_primary_seed.to_le_bytes(),
     ];
-    let (pda_proposal, _proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
+    let (pda_proposal, proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
 
     let expiry: u64 = 3758794966; // Feb 09 2089
     let tx_type: u8 = 0; // Cpi
@@ -1620,7 +3094,7 @@ fn test_execute_transaction_cpi_call() {
         accounts: vec![
             AccountMeta::new(second_admin_pubkey, true), // creator (signer)
             AccountMeta::new(pda_proposal, false),       // proposal_account (will be created)
-            AccountMeta::new_readonly(pda_multisig, false), // multisig_account (readonly)
+            AccountMeta::new(pda_multisig, false),       // multisig_account
             AccountMeta::new_readonly(rent::ID, false),  // rent sysvar
             AccountMeta::new_readonly(clock::ID, false), // clock sysvar
             AccountMeta::new_readonly(system_program::id(), false), // system program
@@ -1690,18 +3164,31 @@ fn test_execute_transaction_cpi_call() {
     assert!(result.is_ok());
     println!("✅ Transaction created with CPI instruction");
 
+    // Approve the proposal
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
     // Execute the transaction
     let execute_transaction_data = vec![5]; // discriminator for ExecuteTransaction instruction
 
     let execute_transaction_instruction = vec![Instruction {
         program_id: program_id,
         accounts: vec![
-            AccountMeta::new(fee_payer.pubkey(), true), // payer (signer)
-            AccountMeta::new(pda_multisig, false),      // multisig
-            AccountMeta::new(pda_proposal, false),      // proposal
-            AccountMeta::new(pda_transaction, false),   // transaction
-            AccountMeta::new(rent::ID, false),          // rent
+            AccountMeta::new(second_admin.pubkey(), true), // payer (signer)
+            AccountMeta::new(pda_multisig, false),         // multisig
+            AccountMeta::new(pda_proposal, false),         // proposal
+            AccountMeta::new(pda_transaction, false),      // transaction
+            AccountMeta::new(rent::ID, false),             // rent
             AccountMeta::new(system_program::id(), false), // system program
+            AccountMeta::new(common::rent_collector(&svm, pda_multisig), false), // rent collector for the vote storage
             AccountMeta::new(source_account.pubkey(), true), // source account (signer)
             AccountMeta::new(destination_account.pubkey(), false), // destination account
         ],
@@ -1717,7 +3204,7 @@ fn test_execute_transaction_cpi_call() {
 
     let result = common::build_and_send_transaction_multisig(
         &mut svm,
-        &fee_payer,
+        &second_admin,
         execute_transaction_instruction,
         &[&source_account], // additional signer for CPI
     );
@@ -0,0 +1,132 @@
+use pinocchio_multisig::{
+    helper::StateDefinition,
+    state::{ProposalState, ProposalStatus, ProposalType},
+};
+use {
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_program::clock::Clock,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+mod common;
+
+fn finalize_instruction(
+    program_id: Pubkey,
+    cranker: Pubkey,
+    multisig: (Pubkey, u8),
+    proposal: Pubkey,
+    creator: Pubkey,
+) -> Instruction {
+    let treasury_seed = [(b"treasury"), multisig.0.as_ref()];
+    let (pda_treasury, _treasury_bump) = Pubkey::find_program_address(&treasury_seed, &program_id);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(cranker, true),
+            AccountMeta::new(multisig.0, false),
+            AccountMeta::new(proposal, false),
+            AccountMeta::new(creator, false),
+            AccountMeta::new(pda_treasury, false),
+        ],
+        data: vec![9, multisig.1],
+    }
+}
+
+#[test]
+fn test_finalize_expired_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    // One yes vote grows the vote storage
+    common::vote(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
+    let cranker = Keypair::new();
+    svm.airdrop(&cranker.pubkey(), 100000000).unwrap();
+
+    // Still open, so it can't be finalized yet
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &cranker,
+        vec![finalize_instruction(
+            program_id,
+            cranker.pubkey(),
+            (pda_multisig, multisig_bump),
+            pda_proposal,
+            second_admin.pubkey(),
+        )],
+    );
+    assert!(result.is_err());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = proposal_state.expiry as i64 + 1;
+    svm.set_sysvar::<Clock>(&clock);
+    svm.expire_blockhash();
+
+    // Expired, but the multisig must still be the PDA of its own primary seed
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &cranker,
+        vec![finalize_instruction(
+            program_id,
+            cranker.pubkey(),
+            (pda_multisig, multisig_bump.wrapping_sub(1)),
+            pda_proposal,
+            second_admin.pubkey(),
+        )],
+    );
+    assert!(result.is_err());
+
+    let creator_lamports_before = svm.get_account(&second_admin.pubkey()).unwrap().lamports;
+
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &cranker,
+        vec![finalize_instruction(
+            program_id,
+            cranker.pubkey(),
+            (pda_multisig, multisig_bump),
+            pda_proposal,
+            second_admin.pubkey(),
+        )],
+    );
+    assert!(result.is_ok());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    assert_eq!(proposal_account.data.len(), ProposalState::LEN);
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.status, ProposalStatus::Failed);
+
+    // The vote storage rent went to the creator
+    let creator_lamports_after = svm.get_account(&second_admin.pubkey()).unwrap().lamports;
+    assert!(creator_lamports_after > creator_lamports_before);
+
+    println!("✅ Success: Expired proposal finalized as Failed!");
+}
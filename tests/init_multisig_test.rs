@@ -1,5 +1,5 @@
 use pinocchio_multisig::helper::account_init::StateDefinition;
-use pinocchio_multisig::state::{MemberState, MultisigState};
+use pinocchio_multisig::state::{MemberState, MultisigState, Permission};
 use {
     solana_instruction::{AccountMeta, Instruction},
     solana_keypair::Keypair,
@@ -12,7 +12,7 @@ use {
 mod common;
 
 #[test]
-fn test_init_multisig_no_members() {
+fn test_init_multisig_no_members_rejected() {
     let (mut svm, fee_payer, _second_admin, program_id) = common::setup_svm_and_program();
     let fee_payer_pubkey = fee_payer.pubkey();
 
@@ -61,20 +61,10 @@ fn test_init_multisig_no_members() {
 
     println!("result: {:?}", result);
 
-    assert!(result.is_ok());
-
-    // Verify the multisig state
-    let multisig_account = svm.get_account(&pda_multisig).unwrap();
-    let multisig_data = &multisig_account.data;
-    let multisig_state: &MultisigState = bytemuck::from_bytes(multisig_data);
+    // A multisig nobody can vote on or execute from is rejected
+    assert!(result.is_err());
 
-    // Check counters
-    assert_eq!(multisig_state.num_members, 0);
-    assert_eq!(multisig_state.admin_counter, 0);
-    assert_eq!(multisig_state.min_threshold, min_threshold);
-    assert_eq!(multisig_state.max_expiry, max_expiry);
-
-    println!("✅ Success: Multisig initialized with 0 members and 0 admins!");
+    println!("✅ Success: Multisig with 0 members correctly rejected!");
 }
 
 #[test]
@@ -165,6 +155,13 @@ fn test_init_multisig_with_members() {
     let third_member_state: &MemberState = bytemuck::from_bytes(third_member_bytes);
     assert_eq!(third_member_state.pubkey, fourth_member.pubkey().to_bytes());
 
+    // Admins get every permission, normal members can vote and execute only
+    assert_eq!(first_member.permissions, MemberState::ALL_PERMISSIONS);
+    assert_eq!(second_member.permissions, MemberState::ALL_PERMISSIONS);
+    assert!(!third_member_state.has_permission(Permission::Initiate));
+    assert!(third_member_state.has_permission(Permission::Vote));
+    assert!(third_member_state.has_permission(Permission::Execute));
+
     println!("✅ Success: Multisig initialized with 2 admins and 1 normal member!");
 }
 
@@ -289,7 +286,7 @@ fn test_init_multisig_account_already_initialized() {
 
     let min_threshold: u8 = 1;
     let max_expiry: u64 = 1_000_000;
-    let num_members: u8 = 0;
+    let num_members: u8 = 1; // fee payer is the only admin
     let primary_seed: u16 = 5;
 
     let data = [
@@ -298,7 +295,7 @@ fn test_init_multisig_account_already_initialized() {
         primary_seed.to_le_bytes().to_vec(),
         min_threshold.to_le_bytes().to_vec(),
         num_members.to_le_bytes().to_vec(),
-        0u8.to_le_bytes().to_vec(),
+        1u8.to_le_bytes().to_vec(),
         vec![0; 3], // 3 bytes of padding for 8-byte alignment
     ]
     .concat();
@@ -322,6 +319,7 @@ fn test_init_multisig_account_already_initialized() {
             AccountMeta::new(pda_treasury, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(fee_payer.pubkey(), false),
         ],
         data: data.clone(),
     }];
@@ -338,6 +336,7 @@ fn test_init_multisig_account_already_initialized() {
             AccountMeta::new(pda_treasury, false),
             AccountMeta::new(rent::ID, false),
             AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(fee_payer.pubkey(), false),
         ],
         data,
     }];
@@ -347,3 +346,188 @@ fn test_init_multisig_account_already_initialized() {
 
     println!("✅ Success: Multisig initialization correctly rejected already initialized account!");
 }
+
+#[test]
+fn test_init_multisig_duplicate_members() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+    let second_admin_pubkey = second_admin.pubkey();
+
+    let min_threshold: u8 = 2;
+    let max_expiry: u64 = 1_000_000;
+    let num_members: u8 = 2;
+    let primary_seed: u16 = 6;
+    let num_admins: u8 = 2;
+
+    let data = [
+        vec![0], // discriminator for InitMultisig instruction
+        max_expiry.to_le_bytes().to_vec(),
+        primary_seed.to_le_bytes().to_vec(),
+        min_threshold.to_le_bytes().to_vec(),
+        num_members.to_le_bytes().to_vec(),
+        num_admins.to_le_bytes().to_vec(),
+        vec![0; 3], // 3 bytes of padding for 8-byte alignment
+    ]
+    .concat();
+
+    // Multisig Config PDA
+    let seed = [(b"multisig"), &primary_seed.to_le_bytes() as &[u8]];
+    let seeds = &seed[..];
+    let (pda_multisig, _multisig_bump) = Pubkey::find_program_address(seeds, &program_id);
+
+    // Treasury PDA
+    let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
+    let treasury_seeds = &treasury_seed[..];
+    let (pda_treasury, _treasury_bump) = Pubkey::find_program_address(treasury_seeds, &program_id);
+
+    // The same key twice would let one signer reach a 2-of-2 threshold alone
+    let instruction = vec![Instruction {
+        program_id: program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_treasury, false),
+            AccountMeta::new(rent::ID, false),
+            AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(second_admin_pubkey, false),
+            AccountMeta::new(second_admin_pubkey, false),
+        ],
+        data,
+    }];
+
+    let result = common::build_and_send_transaction(&mut svm, &fee_payer, instruction);
+    assert!(result.is_err());
+
+    println!("✅ Success: Multisig initialization correctly rejected duplicate members!");
+}
+
+#[test]
+fn test_init_multisig_missing_member_accounts() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+    let second_admin_pubkey = second_admin.pubkey();
+
+    let min_threshold: u8 = 1;
+    let max_expiry: u64 = 1_000_000;
+    let num_members: u8 = 3; // only 1 member account is passed below
+    let primary_seed: u16 = 7;
+    let num_admins: u8 = 1;
+
+    let data = [
+        vec![0], // discriminator for InitMultisig instruction
+        max_expiry.to_le_bytes().to_vec(),
+        primary_seed.to_le_bytes().to_vec(),
+        min_threshold.to_le_bytes().to_vec(),
+        num_members.to_le_bytes().to_vec(),
+        num_admins.to_le_bytes().to_vec(),
+        vec![0; 3], // 3 bytes of padding for 8-byte alignment
+    ]
+    .concat();
+
+    // Multisig Config PDA
+    let seed = [(b"multisig"), &primary_seed.to_le_bytes() as &[u8]];
+    let seeds = &seed[..];
+    let (pda_multisig, _multisig_bump) = Pubkey::find_program_address(seeds, &program_id);
+
+    // Treasury PDA
+    let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
+    let treasury_seeds = &treasury_seed[..];
+    let (pda_treasury, _treasury_bump) = Pubkey::find_program_address(treasury_seeds, &program_id);
+
+    let instruction = vec![Instruction {
+        program_id: program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_treasury, false),
+            AccountMeta::new(rent::ID, false),
+            AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(second_admin_pubkey, false),
+        ],
+        data,
+    }];
+
+    let result = common::build_and_send_transaction(&mut svm, &fee_payer, instruction);
+    assert!(result.is_err());
+
+    println!("✅ Success: Multisig initialization correctly rejected missing member accounts!");
+}
+
+#[test]
+fn test_init_multisig_require_member_signatures() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+    let second_admin_pubkey = second_admin.pubkey();
+
+    let min_threshold: u8 = 1;
+    let max_expiry: u64 = 1_000_000;
+    let num_members: u8 = 1;
+    let primary_seed: u16 = 8;
+    let num_admins: u8 = 1;
+    let require_member_signatures: u8 = 1;
+
+    let data = [
+        vec![0], // discriminator for InitMultisig instruction
+        max_expiry.to_le_bytes().to_vec(),
+        primary_seed.to_le_bytes().to_vec(),
+        min_threshold.to_le_bytes().to_vec(),
+        num_members.to_le_bytes().to_vec(),
+        num_admins.to_le_bytes().to_vec(),
+        require_member_signatures.to_le_bytes().to_vec(),
+        vec![0; 2], // 2 bytes of padding for 8-byte alignment
+    ]
+    .concat();
+
+    // Multisig Config PDA
+    let seed = [(b"multisig"), &primary_seed.to_le_bytes() as &[u8]];
+    let seeds = &seed[..];
+    let (pda_multisig, _multisig_bump) = Pubkey::find_program_address(seeds, &program_id);
+
+    // Treasury PDA
+    let treasury_seed = [(b"treasury"), pda_multisig.as_ref()];
+    let treasury_seeds = &treasury_seed[..];
+    let (pda_treasury, _treasury_bump) = Pubkey::find_program_address(treasury_seeds, &program_id);
+
+    // Member is listed without signing - should fail
+    let unsigned_instruction = vec![Instruction {
+        program_id: program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_treasury, false),
+            AccountMeta::new(rent::ID, false),
+            AccountMeta::new(system_program::id(), false),
+            AccountMeta::new_readonly(second_admin_pubkey, false),
+        ],
+        data: data.clone(),
+    }];
+
+    let result = common::build_and_send_transaction(&mut svm, &fee_payer, unsigned_instruction);
+    assert!(result.is_err());
+
+    // Member co-signs - should succeed
+    let signed_instruction = vec![Instruction {
+        program_id: program_id,
+        accounts: vec![
+            AccountMeta::new(fee_payer.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_treasury, false),
+            AccountMeta::new(rent::ID, false),
+            AccountMeta::new(system_program::id(), false),
+            AccountMeta::new_readonly(second_admin_pubkey, true),
+        ],
+        data,
+    }];
+
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &fee_payer,
+        signed_instruction,
+        &[&second_admin],
+    );
+    assert!(result.is_ok());
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.num_members, 1);
+
+    println!("✅ Success: Initial members must sign when required!");
+}
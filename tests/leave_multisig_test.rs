@@ -0,0 +1,162 @@
+use pinocchio_multisig::{
+    helper::StateDefinition,
+    state::{MemberState, MultisigState},
+};
+use {
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_sysvar::rent,
+};
+
+mod common;
+
+/// The multisig's default rent collector
+fn treasury_pda(program_id: Pubkey, multisig: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury", multisig.as_ref()], &program_id).0
+}
+
+fn leave_instruction(program_id: Pubkey, member: Pubkey, multisig: (Pubkey, u8)) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(member, true),
+            AccountMeta::new(multisig.0, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new(treasury_pda(program_id, multisig.0), false), // rent collector
+        ],
+        data: vec![7, multisig.1],
+    }
+}
+
+#[test]
+fn test_leave_multisig() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    let fourth_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    svm.airdrop(&fourth_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![
+        second_admin.pubkey(),
+        third_admin.pubkey(),
+        fourth_admin.pubkey(),
+    ];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    // The multisig must be the PDA of its own primary seed
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &fee_payer,
+        vec![leave_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump.wrapping_sub(1)),
+        )],
+        &[&third_admin],
+    );
+    assert!(result.is_err());
+
+    // Lamports the multisig holds beyond its rent go to the rent collector, not the leaver
+    let treasury = treasury_pda(program_id, pda_multisig);
+    svm.airdrop(&pda_multisig, 5_000_000).unwrap();
+    let treasury_lamports_before = svm
+        .get_account(&treasury)
+        .map_or(0, |account| account.lamports);
+    let leaver_lamports_before = svm.get_account(&third_admin.pubkey()).unwrap().lamports;
+
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &fee_payer,
+        vec![leave_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump),
+        )],
+        &[&third_admin],
+    );
+    assert!(result.is_ok());
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    assert_eq!(
+        multisig_account.data.len(),
+        MultisigState::LEN + 2 * MemberState::LEN
+    );
+
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.num_members, 2);
+    assert_eq!(multisig_state.admin_counter, 2);
+
+    let members = &multisig_account.data[MultisigState::LEN..];
+    assert!(MemberState::find(members, &third_admin.pubkey().to_bytes())
+        .unwrap()
+        .is_none());
+
+    // Only the freed slot's rent went to the leaver
+    let refund = svm.minimum_balance_for_rent_exemption(MultisigState::LEN + 3 * MemberState::LEN)
+        - svm.minimum_balance_for_rent_exemption(MultisigState::LEN + 2 * MemberState::LEN);
+    assert_eq!(
+        svm.get_account(&third_admin.pubkey()).unwrap().lamports,
+        leaver_lamports_before + refund
+    );
+    assert_eq!(
+        multisig_account.lamports,
+        svm.minimum_balance_for_rent_exemption(MultisigState::LEN + 2 * MemberState::LEN)
+    );
+    assert_eq!(
+        svm.get_account(&treasury).unwrap().lamports,
+        treasury_lamports_before + 5_000_000
+    );
+
+    println!("✅ Success: Member left the multisig and was refunded!");
+}
+
+#[test]
+fn test_leave_multisig_below_threshold_rejected() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    // 2 of 2, so nobody can leave under the default removal policy
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &fee_payer,
+        vec![leave_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump),
+        )],
+        &[&third_admin],
+    );
+    assert!(result.is_err());
+
+    // A key that isn't a member can't leave either
+    let outsider = Keypair::new();
+    let result = common::build_and_send_transaction_multisig(
+        &mut svm,
+        &fee_payer,
+        vec![leave_instruction(
+            program_id,
+            outsider.pubkey(),
+            (pda_multisig, multisig_bump),
+        )],
+        &[&outsider],
+    );
+    assert!(result.is_err());
+
+    let multisig_account = svm.get_account(&pda_multisig).unwrap();
+    let multisig_state: &MultisigState =
+        bytemuck::from_bytes(&multisig_account.data[..MultisigState::LEN]);
+    assert_eq!(multisig_state.num_members, 2);
+
+    println!("✅ Success: Leaving below the threshold was rejected!");
+}
@@ -0,0 +1,326 @@
+use pinocchio_multisig::{
+    helper::{utils::to_bytes, StateDefinition},
+    instructions::{UpdateMultisigIxData, VoteIxData},
+    state::{AccountType, ProposalState, ProposalType, SessionState, TimeUnit},
+    ID,
+};
+use {
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_program::clock::Clock,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_system_program as system_program,
+    solana_sysvar::rent,
+};
+
+mod common;
+
+fn session_pda(program_id: Pubkey, multisig: Pubkey, member: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"session", multisig.as_ref(), member.as_ref()],
+        &program_id,
+    )
+    .0
+}
+
+fn register_session_instruction(
+    program_id: Pubkey,
+    member: Pubkey,
+    multisig: (Pubkey, u8),
+    session_key: Pubkey,
+    duration: u64,
+) -> Instruction {
+    let data = [
+        vec![11],                        // discriminator for RegisterSession instruction
+        duration.to_le_bytes().to_vec(), // duration, 0 revokes the session
+        vec![multisig.1],
+        vec![0; 7], // 7 bytes of padding for 8-byte alignment
+    ]
+    .concat();
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(member, true),
+            AccountMeta::new_readonly(multisig.0, false),
+            AccountMeta::new(session_pda(program_id, multisig.0, member), false),
+            AccountMeta::new_readonly(session_key, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+fn session_vote_instruction(
+    program_id: Pubkey,
+    session_key: Pubkey,
+    multisig: Pubkey,
+    multisig_bump: u8,
+    proposal: Pubkey,
+    proposal_bump: u8,
+    session: Pubkey,
+) -> Instruction {
+    let vote_ix = VoteIxData {
+        multisig_bump,
+        proposal_bump,
+        vote: 1,
+        option: 0,
+    };
+
+    let mut ix_data = vec![3u8];
+    ix_data.extend_from_slice(unsafe { to_bytes(&vote_ix) });
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(session_key, true),
+            AccountMeta::new(multisig, false),
+            AccountMeta::new(proposal, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(session, false),
+        ],
+        data: ix_data,
+    }
+}
+
+#[test]
+fn test_session_key_vote() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    let session_key = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    svm.airdrop(&session_key.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = 1_000;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &third_admin,
+        vec![register_session_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump),
+            session_key.pubkey(),
+            3_600,
+        )],
+    );
+    assert!(result.is_ok());
+
+    let pda_session = session_pda(program_id, pda_multisig, third_admin.pubkey());
+    let session_account = svm.get_account(&pda_session).unwrap();
+    let session: &SessionState = bytemuck::from_bytes(&session_account.data[..SessionState::LEN]);
+    assert_eq!(session.account_type, AccountType::Session as u8);
+    assert_eq!(session.member, third_admin.pubkey().to_bytes());
+    assert_eq!(session.session_key, session_key.pubkey().to_bytes());
+    assert_eq!(session.expiry, 4_600);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &session_key,
+        vec![session_vote_instruction(
+            program_id,
+            session_key.pubkey(),
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            pda_session,
+        )],
+    );
+    assert!(result.is_ok());
+
+    // The vote is the member's own, with no delegate recorded
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_data = &proposal_account.data;
+    let proposal_state = ProposalState::from_bytes(proposal_data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 1);
+    assert_eq!(proposal_state.delegations, 0);
+    assert_eq!(proposal_data.len(), ProposalState::LEN + 32);
+    assert_eq!(
+        &proposal_data[ProposalState::LEN..],
+        third_admin.pubkey().as_ref()
+    );
+
+    println!("✅ Success: Session key voted for its member!");
+}
+
+#[test]
+fn test_session_key_expired() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    let session_key = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    svm.airdrop(&session_key.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = 1_000;
+    svm.set_sysvar::<Clock>(&clock);
+
+    // A zero duration only revokes, there is nothing to revoke yet
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &third_admin,
+        vec![register_session_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump),
+            session_key.pubkey(),
+            0,
+        )],
+    );
+    assert!(result.is_err());
+
+    // Registering against an account that isn't the derived multisig fails too
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &third_admin,
+        vec![register_session_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump.wrapping_sub(1)),
+            session_key.pubkey(),
+            100,
+        )],
+    );
+    assert!(result.is_err());
+
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &third_admin,
+        vec![register_session_instruction(
+            program_id,
+            third_admin.pubkey(),
+            (pda_multisig, multisig_bump),
+            session_key.pubkey(),
+            100,
+        )],
+    );
+    assert!(result.is_ok());
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    clock.unix_timestamp = 1_101;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let pda_session = session_pda(program_id, pda_multisig, third_admin.pubkey());
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &session_key,
+        vec![session_vote_instruction(
+            program_id,
+            session_key.pubkey(),
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            pda_session,
+        )],
+    );
+    assert!(result.is_err());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 0);
+
+    println!("✅ Success: Expired session key can no longer vote!");
+}
+
+#[test]
+fn test_session_key_keeps_its_time_unit() {
+    let (mut svm, second_admin, other_admin, multisig) = common::setup_two_admin_multisig();
+    let program_id = Pubkey::from(ID);
+
+    let session_key = Keypair::new();
+    svm.airdrop(&session_key.pubkey(), 100000000).unwrap();
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = 1_000;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &other_admin,
+        vec![register_session_instruction(
+            program_id,
+            other_admin.pubkey(),
+            multisig,
+            session_key.pubkey(),
+            100,
+        )],
+    );
+    assert!(result.is_ok());
+
+    let update = UpdateMultisigIxData {
+        value: 1,       // TimeUnit::Slot
+        update_type: 8, // 8 for update time unit
+        threshold: 0,
+    };
+    common::apply_multisig_update(&mut svm, &second_admin, &[&second_admin], multisig, update);
+
+    // The slot is long past the session's expiry, but it was set as a timestamp
+    clock.slot = 5_000;
+    clock.unix_timestamp = 1_050;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let pda_session = session_pda(program_id, multisig.0, other_admin.pubkey());
+    let session_account = svm.get_account(&pda_session).unwrap();
+    let session: &SessionState = bytemuck::from_bytes(&session_account.data[..SessionState::LEN]);
+    assert_eq!(session.time_unit, TimeUnit::UnixTimestamp as u8);
+    assert_eq!(session.expiry, 1_100);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        multisig.0,
+        ProposalType::Cpi,
+    );
+    assert_eq!(
+        common::proposal_state(&svm, pda_proposal).created_time,
+        5_000
+    );
+
+    let result = common::build_and_send_transaction(
+        &mut svm,
+        &session_key,
+        vec![session_vote_instruction(
+            program_id,
+            session_key.pubkey(),
+            multisig.0,
+            multisig.1,
+            pda_proposal,
+            proposal_bump,
+            pda_session,
+        )],
+    );
+    assert!(result.is_ok());
+}
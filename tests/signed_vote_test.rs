@@ -0,0 +1,217 @@
+use pinocchio_multisig::{
+    helper::{utils::to_bytes, StateDefinition},
+    instructions::{VoteIxData, SIGNED_VOTE_LEN},
+    state::{ProposalState, ProposalType, VoteNonceState},
+};
+use {
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_program::sysvar::instructions,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_system_program as system_program,
+    solana_sysvar::rent,
+};
+
+mod common;
+
+const ED25519_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("Ed25519SigVerify111111111111111111111111111");
+
+fn vote_nonce_pda(program_id: Pubkey, multisig: Pubkey, member: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"vote_nonce", multisig.as_ref(), member.as_ref()],
+        &program_id,
+    )
+    .0
+}
+
+/// Ed25519 program instruction verifying `member`'s signature over their vote
+fn signed_vote_instruction(
+    member: &Keypair,
+    multisig: Pubkey,
+    proposal: Pubkey,
+    vote: u8,
+    nonce: u64,
+) -> Instruction {
+    let message = [
+        multisig.as_ref(),
+        proposal.as_ref(),
+        &[vote, 0],
+        &nonce.to_le_bytes(),
+    ]
+    .concat();
+    assert_eq!(message.len(), SIGNED_VOTE_LEN);
+
+    let signature = member.sign_message(&message);
+
+    // Header and offsets, then the public key, signature and message they point at
+    let (pubkey_offset, signature_offset, message_offset) = (16u16, 48u16, 112u16);
+    let mut data = vec![1, 0];
+    for value in [
+        signature_offset,
+        u16::MAX,
+        pubkey_offset,
+        u16::MAX,
+        message_offset,
+        SIGNED_VOTE_LEN as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(member.pubkey().as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(&message);
+
+    Instruction {
+        program_id: ED25519_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+fn relayed_vote_instruction(
+    program_id: Pubkey,
+    relayer: Pubkey,
+    multisig: Pubkey,
+    multisig_bump: u8,
+    proposal: Pubkey,
+    proposal_bump: u8,
+    member: Pubkey,
+) -> Instruction {
+    let vote_ix = VoteIxData {
+        multisig_bump,
+        proposal_bump,
+        vote: 1,
+        option: 0,
+    };
+
+    let mut ix_data = vec![3u8];
+    ix_data.extend_from_slice(unsafe { to_bytes(&vote_ix) });
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(relayer, true),
+            AccountMeta::new(multisig, false),
+            AccountMeta::new(proposal, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(instructions::ID, false),
+            AccountMeta::new(vote_nonce_pda(program_id, multisig, member), false),
+        ],
+        data: ix_data,
+    }
+}
+
+#[test]
+fn test_signed_vote_relayed() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    // The third admin holds no SOL, a relayer lands their vote
+    let third_admin = Keypair::new();
+    let relayer = Keypair::new();
+    svm.airdrop(&relayer.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    let relayed_vote = vec![
+        signed_vote_instruction(&third_admin, pda_multisig, pda_proposal, 1, 1),
+        relayed_vote_instruction(
+            program_id,
+            relayer.pubkey(),
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            third_admin.pubkey(),
+        ),
+    ];
+
+    let result = common::build_and_send_transaction(&mut svm, &relayer, relayed_vote.clone());
+    assert!(result.is_ok());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_data = &proposal_account.data;
+    let proposal_state = ProposalState::from_bytes(proposal_data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 1);
+    assert_eq!(
+        &proposal_data[ProposalState::LEN..],
+        third_admin.pubkey().as_ref()
+    );
+
+    let pda_nonce = vote_nonce_pda(program_id, pda_multisig, third_admin.pubkey());
+    let nonce_account = svm.get_account(&pda_nonce).unwrap();
+    let nonce_state: &VoteNonceState =
+        bytemuck::from_bytes(&nonce_account.data[..VoteNonceState::LEN]);
+    assert_eq!(nonce_state.member, third_admin.pubkey().to_bytes());
+    assert_eq!(nonce_state.nonce, 1);
+
+    // The same signed approval can't be submitted twice
+    svm.expire_blockhash();
+    let result = common::build_and_send_transaction(&mut svm, &relayer, relayed_vote);
+    assert!(result.is_err());
+
+    println!("✅ Success: Relayed signed vote recorded for the member!");
+}
+
+#[test]
+fn test_signed_vote_mismatch() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    let relayer = Keypair::new();
+    svm.airdrop(&relayer.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    let relay = || {
+        relayed_vote_instruction(
+            program_id,
+            relayer.pubkey(),
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            third_admin.pubkey(),
+        )
+    };
+
+    // A relayer can't flip the signed vote, nor relay without a signature at all
+    for instructions in [
+        vec![
+            signed_vote_instruction(&third_admin, pda_multisig, pda_proposal, 0, 1),
+            relay(),
+        ],
+        vec![relay()],
+    ] {
+        let result = common::build_and_send_transaction(&mut svm, &relayer, instructions);
+        assert!(result.is_err());
+    }
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 0);
+
+    println!("✅ Success: Relayed vote must match the member's signature!");
+}
@@ -1,8 +1,17 @@
 use pinocchio_multisig::{
-    helper::StateDefinition,
-    state::{ProposalState, ProposalType},
+    helper::{utils::to_bytes, StateDefinition},
+    instructions::VoteIxData,
+    state::{ProposalState, ProposalStatus, ProposalType},
+};
+use {
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_program::clock::Clock,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_system_program as system_program,
+    solana_sysvar::{clock, rent},
 };
-use {solana_keypair::Keypair, solana_signer::Signer};
 
 mod common;
 
@@ -152,7 +161,13 @@ pub fn test_change_vote_from_no_to_yes() {
 
     let third_admin = Keypair::new();
     svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
-    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+    // Extra admins keep the rejections below from making the 2 vote threshold unreachable
+    let admins = vec![
+        second_admin.pubkey(),
+        third_admin.pubkey(),
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+    ];
 
     let (pda_multisig, multisig_bump) =
         common::create_multisig(&mut svm, &fee_payer, program_id, admins);
@@ -285,7 +300,13 @@ pub fn test_multiple_votes_no_then_yes() {
 
     let third_admin = Keypair::new();
     svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
-    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+    // Extra admins keep the rejections below from making the 2 vote threshold unreachable
+    let admins = vec![
+        second_admin.pubkey(),
+        third_admin.pubkey(),
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+    ];
     let (pda_multisig, multisig_bump) =
         common::create_multisig(&mut svm, &fee_payer, program_id, admins);
 
@@ -351,7 +372,13 @@ pub fn test_vote_alternating_pattern() {
 
     let third_admin = Keypair::new();
     svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
-    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+    // Extra admins keep the rejections below from making the 2 vote threshold unreachable
+    let admins = vec![
+        second_admin.pubkey(),
+        third_admin.pubkey(),
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+    ];
 
     let (pda_multisig, multisig_bump) =
         common::create_multisig(&mut svm, &fee_payer, program_id, admins);
@@ -443,3 +470,447 @@ pub fn test_vote_alternating_pattern() {
     assert_eq!(proposal_votes.len(), 1);
     assert_eq!(proposal_votes[0], second_admin.pubkey().as_ref());
 }
+
+#[test]
+pub fn test_vote_records_approval_time() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = 1_000;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    // One yes vote is below the 2 vote threshold
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.approved_time, 0);
+
+    // The second yes vote reaches it
+    common::vote(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.approved_time, 1_000);
+
+    // Switching to no drops below the threshold again
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        0,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.approved_time, 0);
+}
+
+#[test]
+pub fn test_vote_after_deadline_rejected() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = proposal_state.expiry as i64 + 1;
+    svm.set_sysvar::<Clock>(&clock);
+
+    let vote_ix = VoteIxData {
+        multisig_bump,
+        proposal_bump,
+        vote: 1,
+        option: 0,
+    };
+
+    let mut ix_data = vec![3u8];
+    ix_data.extend_from_slice(unsafe { to_bytes(&vote_ix) });
+
+    let vote_instruction = vec![Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(second_admin.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_proposal, false),
+        ],
+        data: ix_data,
+    }];
+
+    let result = common::build_and_send_transaction(&mut svm, &second_admin, vote_instruction);
+    assert!(result.is_err());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 0);
+}
+
+#[test]
+pub fn test_vote_no_fails_unreachable_proposal() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    // In a 2 of 2 multisig a single rejection settles it
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        0,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.no_votes, 1);
+    assert_eq!(proposal_state.status, ProposalStatus::Failed);
+
+    // Failed proposals take no more votes
+    let vote_ix = VoteIxData {
+        multisig_bump,
+        proposal_bump,
+        vote: 1,
+        option: 0,
+    };
+
+    let mut ix_data = vec![3u8];
+    ix_data.extend_from_slice(unsafe { to_bytes(&vote_ix) });
+
+    let vote_instruction = vec![Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(third_admin.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_proposal, false),
+        ],
+        data: ix_data,
+    }];
+
+    let result = common::build_and_send_transaction(&mut svm, &third_admin, vote_instruction);
+    assert!(result.is_err());
+}
+
+#[test]
+pub fn test_vote_abstain() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![second_admin.pubkey(), third_admin.pubkey()];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    let (pda_proposal, proposal_bump) = common::create_proposal(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        ProposalType::Cpi,
+    );
+
+    common::vote(
+        &mut svm,
+        &second_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        1,
+    );
+
+    // Abstain
+    common::vote(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        2,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 1);
+    assert_eq!(proposal_state.no_votes, 0);
+    assert_eq!(proposal_state.abstain_votes, 1);
+    assert_eq!(proposal_state.participation(), 2);
+
+    // Change vote: Abstain to No, which moves between the yes and abstain sections
+    common::vote(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        0,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_data = &proposal_account.data;
+    let proposal_state = ProposalState::from_bytes(proposal_data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 1);
+    assert_eq!(proposal_state.no_votes, 1);
+    assert_eq!(proposal_state.abstain_votes, 0);
+
+    let proposal_votes_data = &proposal_data[ProposalState::LEN..];
+    let proposal_votes = proposal_votes_data.chunks_exact(32).collect::<Vec<&[u8]>>();
+    assert_eq!(proposal_votes.len(), 2);
+    assert_eq!(proposal_votes[0], second_admin.pubkey().as_ref());
+    assert_eq!(proposal_votes[1], third_admin.pubkey().as_ref());
+}
+
+fn vote_for_option(
+    svm: &mut litesvm::LiteSVM,
+    voter: &Keypair,
+    program_id: Pubkey,
+    pda_multisig: Pubkey,
+    multisig_bump: u8,
+    pda_proposal: Pubkey,
+    proposal_bump: u8,
+    option: u8,
+) {
+    let vote_ix = VoteIxData {
+        multisig_bump,
+        proposal_bump,
+        vote: 1,
+        option,
+    };
+
+    let mut ix_data = vec![3u8];
+    ix_data.extend_from_slice(unsafe { to_bytes(&vote_ix) });
+
+    let vote_instruction = vec![Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(voter.pubkey(), true),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new(pda_proposal, false),
+            AccountMeta::new(rent::ID, false),
+            AccountMeta::new(system_program::id(), false),
+        ],
+        data: ix_data,
+    }];
+
+    let result = common::build_and_send_transaction(svm, voter, vote_instruction);
+    assert!(result.is_ok());
+}
+
+#[test]
+pub fn test_vote_multi_choice_options() {
+    let (mut svm, fee_payer, second_admin, program_id) = common::setup_svm_and_program();
+
+    let third_admin = Keypair::new();
+    let fourth_admin = Keypair::new();
+    svm.airdrop(&third_admin.pubkey(), 100000000).unwrap();
+    svm.airdrop(&fourth_admin.pubkey(), 100000000).unwrap();
+    let admins = vec![
+        second_admin.pubkey(),
+        third_admin.pubkey(),
+        fourth_admin.pubkey(),
+    ];
+
+    let (pda_multisig, multisig_bump) =
+        common::create_multisig(&mut svm, &fee_payer, program_id, admins);
+
+    // Two candidate transactions, e.g. paying vendor A or vendor B
+    let mut pda_transactions = vec![];
+    for transaction_primary_seed in [40u16, 41u16] {
+        let mut tx_buffer = [0u8; 512];
+        tx_buffer[..32].copy_from_slice(system_program::id().as_ref());
+        tx_buffer[32] = transaction_primary_seed as u8;
+        let buffer_size: u16 = 33;
+
+        let create_transaction_data = [
+            vec![4],                     // discriminator for CreateTransaction instruction
+            0u64.to_le_bytes().to_vec(), // transaction_index
+            transaction_primary_seed.to_le_bytes().to_vec(),
+            tx_buffer.to_vec(),
+            buffer_size.to_le_bytes().to_vec(),
+            vec![0; 4], // 4 bytes of padding for 8-byte alignment
+        ]
+        .concat();
+
+        let transaction_seed = [
+            b"transaction".as_ref(),
+            &transaction_primary_seed.to_le_bytes(),
+        ];
+        let (pda_transaction, _) = Pubkey::find_program_address(&transaction_seed, &program_id);
+
+        let create_transaction_instruction = vec![Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(fee_payer.pubkey(), true),
+                AccountMeta::new(pda_transaction, false),
+                AccountMeta::new(rent::ID, false),
+                AccountMeta::new(system_program::id(), false),
+            ],
+            data: create_transaction_data,
+        }];
+
+        let result = common::build_and_send_transaction(
+            &mut svm,
+            &fee_payer,
+            create_transaction_instruction,
+        );
+        assert!(result.is_ok());
+
+        pda_transactions.push(pda_transaction);
+    }
+
+    let proposal_primary_seed: u16 = 0;
+    let proposal_seed = [
+        b"proposal".as_ref(),
+        pda_multisig.as_ref(),
+        &proposal_primary_seed.to_le_bytes(),
+    ];
+    let (pda_proposal, proposal_bump) = Pubkey::find_program_address(&proposal_seed, &program_id);
+
+    let create_proposal_data = [
+        vec![2],                                      // discriminator (CreateProposal)
+        0u64.to_le_bytes().to_vec(),                  // expiry: u64 (8 bytes)
+        proposal_primary_seed.to_le_bytes().to_vec(), // primary_seed: u16 (2 bytes)
+        vec![0],                                      // tx_type: u8 (1 byte)
+        vec![0],                                      // optimistic: u8 (1 byte)
+        vec![2],                                      // options: u8 (1 byte)
+        vec![0; 3],
+    ]
+    .concat();
+
+    let create_proposal_instruction = vec![Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(second_admin.pubkey(), true),
+            AccountMeta::new(pda_proposal, false),
+            AccountMeta::new(pda_multisig, false),
+            AccountMeta::new_readonly(rent::ID, false),
+            AccountMeta::new_readonly(clock::ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(pda_transactions[0], false),
+            AccountMeta::new_readonly(pda_transactions[1], false),
+        ],
+        data: create_proposal_data,
+    }];
+
+    let result =
+        common::build_and_send_transaction(&mut svm, &second_admin, create_proposal_instruction);
+    assert!(result.is_ok());
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_state = ProposalState::from_bytes(&proposal_account.data).unwrap();
+    assert_eq!(proposal_state.options, 2);
+    assert_eq!(proposal_state.candidates[0], pda_transactions[0].to_bytes());
+    assert_eq!(proposal_state.candidates[1], pda_transactions[1].to_bytes());
+
+    // Both vote for the second option, then one of them switches to the first
+    for voter in [&second_admin, &third_admin] {
+        vote_for_option(
+            &mut svm,
+            voter,
+            program_id,
+            pda_multisig,
+            multisig_bump,
+            pda_proposal,
+            proposal_bump,
+            1,
+        );
+    }
+    vote_for_option(
+        &mut svm,
+        &third_admin,
+        program_id,
+        pda_multisig,
+        multisig_bump,
+        pda_proposal,
+        proposal_bump,
+        0,
+    );
+
+    let proposal_account = svm.get_account(&pda_proposal).unwrap();
+    let proposal_data = &proposal_account.data;
+    let proposal_state = ProposalState::from_bytes(proposal_data).unwrap();
+    assert_eq!(proposal_state.yes_votes, 2);
+    assert_eq!(proposal_state.option_votes[..2], [1, 1]);
+
+    // Yes voters are grouped by option
+    let proposal_votes = proposal_data[ProposalState::LEN..]
+        .chunks_exact(32)
+        .collect::<Vec<&[u8]>>();
+    assert_eq!(proposal_votes[0], third_admin.pubkey().as_ref());
+    assert_eq!(proposal_votes[1], second_admin.pubkey().as_ref());
+    assert_eq!(
+        proposal_state.option_of(&pda_transactions[1].to_bytes()),
+        Ok(1)
+    );
+    assert_eq!(proposal_state.option_voters(1), 1..2);
+}